@@ -1,43 +1,193 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::env;
+
+// The ASCII variant is deliberately kept inline so counting a line never allocates.
+#[allow(clippy::large_enum_variant)]
+enum LetterCounts {
+    Ascii([u32; 128]),
+    Chars(HashMap<char, u32>),
+}
+
+impl From<&str> for LetterCounts {
+    fn from(line: &str) -> Self {
+        if !line.is_ascii() {
+            return LetterCounts::Chars(char_counts(line));
+        }
+
+        let mut counts = [0; 128];
+        for &byte in line.as_bytes() {
+            counts[byte as usize] += 1;
+        }
+        LetterCounts::Ascii(counts)
+    }
+}
+
+impl LetterCounts {
+    fn contains(&self, multiplicity: u32) -> bool {
+        if multiplicity == 0 {
+            return false;
+        }
+
+        match self {
+            LetterCounts::Ascii(counts) => counts.contains(&multiplicity),
+            LetterCounts::Chars(counts) => counts.values().any(|&count| count == multiplicity),
+        }
+    }
+
+    fn multiplicities(&self) -> Vec<u32> {
+        let mut multiplicities: Vec<u32> = match self {
+            LetterCounts::Ascii(counts) => counts.iter().copied().filter(|&n| n > 0).collect(),
+            LetterCounts::Chars(counts) => counts.values().copied().collect(),
+        };
+        multiplicities.sort_unstable();
+        multiplicities.dedup();
+        multiplicities
+    }
+}
 
 fn main() {
     let input = include_str!("./input.txt");
-    let total = get_checksum(input);
-    println!("{}", total);
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("histogram") => {
+            for (multiplicity, ids) in multiplicity_histogram(input) {
+                println!("{}: {}", multiplicity, ids);
+            }
+        }
+        Some("checksum") => {
+            let combine = match args.get(1).map(String::as_str) {
+                Some("sum") => sum,
+                _ => product,
+            };
+            let multiplicities: Vec<u32> = args
+                .get(2)
+                .map(|list| list.split(',').filter_map(|m| m.parse().ok()).collect())
+                .unwrap_or_else(|| vec![2, 3]);
+            println!("{}", get_checksum_with(input, &multiplicities, combine));
+        }
+        _ => {
+            let total = get_checksum(input);
+            println!("{}", total);
+        }
+    }
 }
 
 fn get_checksum(input: &str) -> u32 {
-    let (twos, threes) = input
-        .lines()
-        .map(into_counts)
-        .fold((0, 0), |acc, curr| (acc.0 + curr.0, acc.1 + curr.1));
-    twos * threes
+    get_checksum_with(input, &[2, 3], product)
 }
 
-fn into_counts(line: &str) -> (u32, u32) {
-    let char_counts = line.chars().fold(HashMap::new(), |mut acc, curr| {
-        *acc.entry(curr).or_insert(0) += 1;
-        acc
-    });
+fn get_checksum_with<F>(input: &str, multiplicities: &[u32], combine: F) -> u32
+where
+    F: Fn(&[u32]) -> u32,
+{
+    let mut totals = vec![0; multiplicities.len()];
+
+    for line in input.lines() {
+        let counts = LetterCounts::from(line);
+        for (total, &multiplicity) in totals.iter_mut().zip(multiplicities) {
+            if counts.contains(multiplicity) {
+                *total += 1;
+            }
+        }
+    }
+
+    combine(&totals)
+}
+
+fn product(totals: &[u32]) -> u32 {
+    totals.iter().product()
+}
 
-    let (twos, threes) = char_counts
-        .values()
-        .fold((0, 0), |(twos, threes), count| match count {
-            2 => (1, threes),
-            3 => (twos, 1),
-            _ => (twos, threes),
-        });
+fn sum(totals: &[u32]) -> u32 {
+    totals.iter().sum()
+}
+
+fn multiplicity_histogram(input: &str) -> BTreeMap<u32, u32> {
+    input.lines().fold(BTreeMap::new(), |mut histogram, line| {
+        for multiplicity in LetterCounts::from(line).multiplicities() {
+            *histogram.entry(multiplicity).or_insert(0) += 1;
+        }
+        histogram
+    })
+}
 
-    (twos, threes)
+fn char_counts(line: &str) -> HashMap<char, u32> {
+    line.chars().fold(HashMap::new(), |mut acc, curr| {
+        *acc.entry(curr).or_insert(0) += 1;
+        acc
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab";
+
     #[test]
     fn test_1() {
-        let input = "abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab";
-        assert_eq!(get_checksum(input), 12);
+        assert_eq!(get_checksum(EXAMPLE), 12);
+    }
+
+    #[test]
+    fn checksum_with_other_rules() {
+        assert_eq!(get_checksum_with(EXAMPLE, &[2, 3], sum), 7);
+        assert_eq!(get_checksum_with(EXAMPLE, &[1], product), 6);
+        assert_eq!(
+            get_checksum_with(EXAMPLE, &[2, 3], |totals| totals[0] - totals[1]),
+            1
+        );
+    }
+
+    #[test]
+    fn non_ascii_ids_use_char_counts() {
+        let input = "ééa\nüüüb\nabcdef";
+        assert!(matches!(LetterCounts::from("ééa"), LetterCounts::Chars(_)));
+        assert!(matches!(
+            LetterCounts::from("abcdef"),
+            LetterCounts::Ascii(_)
+        ));
+        assert_eq!(get_checksum(input), 1);
+        assert_eq!(get_checksum_with(input, &[1, 2, 3], sum), 5);
+    }
+
+    #[test]
+    fn histogram() {
+        let histogram: Vec<(u32, u32)> = multiplicity_histogram(EXAMPLE).into_iter().collect();
+        assert_eq!(histogram, vec![(1, 6), (2, 4), (3, 3)]);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_ascii_vs_char_counts() {
+        use std::time::Instant;
+
+        let input: String = (0..200_000u32)
+            .map(|i| {
+                (0..26)
+                    .map(|j| (b'a' + ((i * 7 + j * j) % 26) as u8) as char)
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let start = Instant::now();
+        let ascii: usize = input
+            .lines()
+            .filter(|line| LetterCounts::from(*line).contains(2))
+            .count();
+        let ascii_time = start.elapsed();
+
+        let start = Instant::now();
+        let chars: usize = input
+            .lines()
+            .filter(|line| LetterCounts::Chars(char_counts(line)).contains(2))
+            .count();
+        let chars_time = start.elapsed();
+
+        assert_eq!(ascii, chars);
+        println!("ascii: {:?}, chars: {:?}", ascii_time, chars_time);
     }
 }
@@ -0,0 +1,125 @@
+use crate::SimilarPair;
+
+#[derive(Debug, PartialEq)]
+pub struct Cluster<'a> {
+    pub members: Vec<(usize, &'a str)>,
+}
+
+impl Cluster<'_> {
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        UnionFind {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, node: usize) -> usize {
+        let mut root = node;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut curr = node;
+        while self.parents[curr] != root {
+            let next = self.parents[curr];
+            self.parents[curr] = root;
+            curr = next;
+        }
+
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut root_a, mut root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return;
+        }
+
+        if self.sizes[root_a] < self.sizes[root_b] {
+            std::mem::swap(&mut root_a, &mut root_b);
+        }
+        self.parents[root_b] = root_a;
+        self.sizes[root_a] += self.sizes[root_b];
+    }
+}
+
+// Every line becomes a member of exactly one cluster, so IDs with no similar
+// partner come back as clusters of size one. Clusters are ordered largest
+// first, then by their first line number.
+pub fn cluster_ids<'a>(input: &'a str, pairs: &[SimilarPair<'a>]) -> Vec<Cluster<'a>> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut sets = UnionFind::new(lines.len());
+
+    for pair in pairs {
+        sets.union(pair.line_a - 1, pair.line_b - 1);
+    }
+
+    let mut clusters: Vec<Cluster> = Vec::new();
+    let mut cluster_for_root = vec![None; lines.len()];
+
+    for (i, line) in lines.iter().enumerate() {
+        let root = sets.find(i);
+        let index = *cluster_for_root[root].get_or_insert_with(|| {
+            clusters.push(Cluster {
+                members: Vec::new(),
+            });
+            clusters.len() - 1
+        });
+        clusters[index].members.push((i + 1, line));
+    }
+
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.size()));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_similar_pairs;
+    use crate::similarity::Metric;
+
+    #[test]
+    fn clusters_chain_through_shared_neighbours() {
+        let input = "abcde\nabcdx\nfghij\nabcyx\nzzzzz\nfguij";
+        let pairs = find_similar_pairs(input, Metric::Hamming, 1);
+        let clusters = cluster_ids(input, &pairs);
+
+        assert_eq!(
+            clusters,
+            vec![
+                Cluster {
+                    members: vec![(1, "abcde"), (2, "abcdx"), (4, "abcyx")],
+                },
+                Cluster {
+                    members: vec![(3, "fghij"), (6, "fguij")],
+                },
+                Cluster {
+                    members: vec![(5, "zzzzz")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn threshold_merges_clusters() {
+        let input = "abcde\nabcdx\nabcyz";
+        let pairs = find_similar_pairs(input, Metric::Hamming, 2);
+        let sizes: Vec<usize> = cluster_ids(input, &pairs)
+            .iter()
+            .map(Cluster::size)
+            .collect();
+
+        assert_eq!(sizes, vec![3]);
+    }
+}
@@ -1,36 +1,154 @@
+mod cluster;
+mod similarity;
+
+use similarity::Metric;
+use std::env;
+
+#[derive(Debug, PartialEq)]
+struct NearDuplicate<'a> {
+    line_a: usize,
+    id_a: &'a str,
+    line_b: usize,
+    id_b: &'a str,
+    index: usize,
+    char_a: char,
+    char_b: char,
+    common_text: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct SimilarPair<'a> {
+    line_a: usize,
+    id_a: &'a str,
+    line_b: usize,
+    id_b: &'a str,
+    distance: usize,
+}
+
 fn main() {
     let input = include_str!("./input.txt");
-    let total = get_text(input);
-    println!("{}", total);
-}
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("all") => {
+            for dup in find_near_duplicates(input) {
+                println!(
+                    "{}:{} {}:{} index {} ({} != {}) {}",
+                    dup.line_a,
+                    dup.id_a,
+                    dup.line_b,
+                    dup.id_b,
+                    dup.index,
+                    dup.char_a,
+                    dup.char_b,
+                    dup.common_text
+                );
+            }
+        }
+        Some("similar") => {
+            let (metric, max_distance) = parse_threshold(&args[1..]);
+            for pair in find_similar_pairs(input, metric, max_distance) {
+                println!(
+                    "{}:{} {}:{} distance {}",
+                    pair.line_a, pair.id_a, pair.line_b, pair.id_b, pair.distance
+                );
+            }
+        }
+        Some("clusters") => {
+            let (metric, max_distance) = parse_threshold(&args[1..]);
+            let pairs = find_similar_pairs(input, metric, max_distance);
+            let clusters = cluster::cluster_ids(input, &pairs);
 
-fn get_text(input: &str) -> String {
-    for line_a in input.lines() {
-        for line_b in input.lines() {
-            let (distance, common_text) = process_lines(line_a, line_b);
-            if distance == 1 {
-                return common_text;
+            let singletons = clusters.iter().filter(|c| c.size() == 1).count();
+            for cluster in clusters.iter().filter(|c| c.size() > 1) {
+                let members: Vec<String> = cluster
+                    .members
+                    .iter()
+                    .map(|(line, id)| format!("{}:{}", line, id))
+                    .collect();
+                println!("{} {}", cluster.size(), members.join(" "));
             }
+            println!("{} ids with no similar partner", singletons);
         }
+        _ => match get_text(input) {
+            Some(text) => println!("{}", text),
+            None => println!("match not found"),
+        },
     }
+}
+
+fn parse_threshold(args: &[String]) -> (Metric, usize) {
+    let metric = match args.first().map(String::as_str) {
+        Some("levenshtein") => Metric::Levenshtein,
+        _ => Metric::Hamming,
+    };
+    let max_distance = args.get(1).and_then(|k| k.parse().ok()).unwrap_or(1);
+
+    (metric, max_distance)
+}
 
-    String::from("match not found")
+fn get_text(input: &str) -> Option<String> {
+    find_near_duplicates(input)
+        .into_iter()
+        .next()
+        .map(|dup| dup.common_text)
 }
 
-fn process_lines(line_a: &str, line_b: &str) -> (u32, String) {
-    let (distance, common) =
-        line_a
-            .chars()
-            .zip(line_b.chars())
-            .fold((0, String::new()), |(dist, mut text), (a, b)| {
-                if a == b {
-                    text.push(a);
-                    return (dist, text);
-                }
-                (dist + 1, text)
+fn find_near_duplicates(input: &str) -> Vec<NearDuplicate<'_>> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut matches = Vec::new();
+
+    for (i, line_a) in lines.iter().enumerate() {
+        for (j, line_b) in lines.iter().enumerate().skip(i + 1) {
+            if similarity::hamming(line_a, line_b) != Some(1) {
+                continue;
+            }
+
+            let (index, (offset, char_a), char_b) = line_a
+                .char_indices()
+                .zip(line_b.chars())
+                .enumerate()
+                .map(|(index, (a, b))| (index, a, b))
+                .find(|(_index, (_offset, a), b)| a != b)
+                .expect("lines at distance 1 must differ somewhere");
+
+            let common_text = [&line_a[..offset], &line_a[offset + char_a.len_utf8()..]].concat();
+
+            matches.push(NearDuplicate {
+                line_a: i + 1,
+                id_a: line_a,
+                line_b: j + 1,
+                id_b: line_b,
+                index,
+                char_a,
+                char_b,
+                common_text,
             });
+        }
+    }
 
-    (distance, common)
+    matches
+}
+
+fn find_similar_pairs(input: &str, metric: Metric, max_distance: usize) -> Vec<SimilarPair<'_>> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut pairs = Vec::new();
+
+    for (i, line_a) in lines.iter().enumerate() {
+        for (j, line_b) in lines.iter().enumerate().skip(i + 1) {
+            if let Some(distance) = metric.within(line_a, line_b, max_distance) {
+                pairs.push(SimilarPair {
+                    line_a: i + 1,
+                    id_a: line_a,
+                    line_b: j + 1,
+                    id_b: line_b,
+                    distance,
+                });
+            }
+        }
+    }
+
+    pairs
 }
 
 #[cfg(test)]
@@ -40,6 +158,90 @@ mod tests {
     #[test]
     fn test_1() {
         let input = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz";
-        assert_eq!(get_text(input), "fgij");
+        assert_eq!(get_text(input), Some(String::from("fgij")));
+    }
+
+    #[test]
+    fn no_match() {
+        let input = "abcde\nfghij\nklmno";
+        assert_eq!(get_text(input), None);
+        assert!(find_near_duplicates(input).is_empty());
+    }
+
+    #[test]
+    fn all_near_duplicates() {
+        let input = "abcde\nfghij\nabcdx\nfguij\nabxde";
+        let dups = find_near_duplicates(input);
+        assert_eq!(
+            dups,
+            vec![
+                NearDuplicate {
+                    line_a: 1,
+                    id_a: "abcde",
+                    line_b: 3,
+                    id_b: "abcdx",
+                    index: 4,
+                    char_a: 'e',
+                    char_b: 'x',
+                    common_text: String::from("abcd"),
+                },
+                NearDuplicate {
+                    line_a: 1,
+                    id_a: "abcde",
+                    line_b: 5,
+                    id_b: "abxde",
+                    index: 2,
+                    char_a: 'c',
+                    char_b: 'x',
+                    common_text: String::from("abde"),
+                },
+                NearDuplicate {
+                    line_a: 2,
+                    id_a: "fghij",
+                    line_b: 4,
+                    id_b: "fguij",
+                    index: 2,
+                    char_a: 'h',
+                    char_b: 'u',
+                    common_text: String::from("fgij"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn non_ascii_near_duplicates() {
+        let input = "héllo\nwörld\nhéllø";
+        let dups = find_near_duplicates(input);
+        assert_eq!(dups.len(), 1);
+        assert_eq!(dups[0].index, 4);
+        assert_eq!(dups[0].char_a, 'o');
+        assert_eq!(dups[0].char_b, 'ø');
+        assert_eq!(dups[0].common_text, "héll");
+    }
+
+    #[test]
+    fn unequal_lengths_are_not_truncated() {
+        let input = "abc\nabxd\nabcdef";
+        assert_eq!(get_text(input), None);
+        assert!(find_similar_pairs(input, Metric::Hamming, 1).is_empty());
+    }
+
+    #[test]
+    fn similar_pairs_within_threshold() {
+        let input = "abcde\naxcye\nabcd";
+
+        let hamming: Vec<(usize, usize, usize)> = find_similar_pairs(input, Metric::Hamming, 2)
+            .iter()
+            .map(|pair| (pair.line_a, pair.line_b, pair.distance))
+            .collect();
+        assert_eq!(hamming, vec![(1, 2, 2)]);
+
+        let levenshtein: Vec<(usize, usize, usize)> =
+            find_similar_pairs(input, Metric::Levenshtein, 1)
+                .iter()
+                .map(|pair| (pair.line_a, pair.line_b, pair.distance))
+                .collect();
+        assert_eq!(levenshtein, vec![(1, 3, 1)]);
     }
 }
@@ -0,0 +1,162 @@
+use std::cmp::min;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    Hamming,
+    Levenshtein,
+}
+
+impl Metric {
+    pub fn distance(self, a: &str, b: &str) -> Option<usize> {
+        match self {
+            Metric::Hamming => hamming(a, b),
+            Metric::Levenshtein => Some(levenshtein(a, b)),
+        }
+    }
+
+    pub fn within(self, a: &str, b: &str, max_distance: usize) -> Option<usize> {
+        self.distance(a, b)
+            .filter(|&distance| distance <= max_distance)
+    }
+}
+
+// Hamming distance is only defined for strings of equal length, so anything
+// else is reported as incomparable rather than truncated to the shorter one.
+pub fn hamming(a: &str, b: &str) -> Option<usize> {
+    if a.is_ascii() && b.is_ascii() {
+        return hamming_slices(a.as_bytes(), b.as_bytes());
+    }
+
+    hamming_chars(a, b)
+}
+
+fn hamming_slices<T: PartialEq>(a: &[T], b: &[T]) -> Option<usize> {
+    if a.len() != b.len() {
+        return None;
+    }
+
+    Some(a.iter().zip(b).filter(|(x, y)| x != y).count())
+}
+
+fn hamming_chars(a: &str, b: &str) -> Option<usize> {
+    let mut a_chars = a.chars();
+    let mut b_chars = b.chars();
+    let mut distance = 0;
+
+    loop {
+        match (a_chars.next(), b_chars.next()) {
+            (Some(x), Some(y)) => {
+                if x != y {
+                    distance += 1;
+                }
+            }
+            (None, None) => return Some(distance),
+            _ => return None,
+        }
+    }
+}
+
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    if a.is_ascii() && b.is_ascii() {
+        return levenshtein_slices(a.as_bytes(), b.as_bytes());
+    }
+
+    let a_chars: Vec<char> = a.chars().collect();
+    let b_chars: Vec<char> = b.chars().collect();
+    levenshtein_slices(&a_chars, &b_chars)
+}
+
+fn levenshtein_slices<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_item) in a.iter().enumerate() {
+        current[0] = i + 1;
+
+        for (j, b_item) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_item == b_item { 0 } else { 1 };
+            let insertion = current[j] + 1;
+            let deletion = previous[j + 1] + 1;
+            current[j + 1] = min(substitution, min(insertion, deletion));
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hamming_equal_lengths() {
+        assert_eq!(hamming("abcde", "axcye"), Some(2));
+        assert_eq!(hamming("fghij", "fguij"), Some(1));
+        assert_eq!(hamming("", ""), Some(0));
+    }
+
+    #[test]
+    fn hamming_does_not_truncate() {
+        assert_eq!(hamming("abc", "abcd"), None);
+        assert_eq!(hamming("abxd", "abc"), None);
+    }
+
+    #[test]
+    fn non_ascii_distances() {
+        assert_eq!(hamming("héllo", "hallo"), Some(1));
+        assert_eq!(hamming("héllo", "hello!"), None);
+        assert_eq!(levenshtein("héllo", "hllo"), 1);
+        assert_eq!(levenshtein("ü", "u"), 1);
+    }
+
+    #[test]
+    fn levenshtein_distances() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("abc", "abcd"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("fghij", "fguij"), 1);
+    }
+
+    #[test]
+    fn within_threshold() {
+        assert_eq!(Metric::Hamming.within("abcde", "axcye", 1), None);
+        assert_eq!(Metric::Hamming.within("abcde", "axcye", 2), Some(2));
+        assert_eq!(Metric::Hamming.within("abc", "abcd", 5), None);
+        assert_eq!(Metric::Levenshtein.within("abc", "abcd", 1), Some(1));
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_ascii_vs_char_hamming() {
+        use std::time::Instant;
+
+        let ids: Vec<String> = (0..3_000u32)
+            .map(|i| {
+                (0..26)
+                    .map(|j| (b'a' + ((i * 7 + j * j) % 26) as u8) as char)
+                    .collect()
+            })
+            .collect();
+
+        let start = Instant::now();
+        let ascii = ids
+            .iter()
+            .flat_map(|a| ids.iter().map(move |b| hamming(a, b)))
+            .filter(|&distance| distance == Some(1))
+            .count();
+        let ascii_time = start.elapsed();
+
+        let start = Instant::now();
+        let chars = ids
+            .iter()
+            .flat_map(|a| ids.iter().map(move |b| hamming_chars(a, b)))
+            .filter(|&distance| distance == Some(1))
+            .count();
+        let chars_time = start.elapsed();
+
+        assert_eq!(ascii, chars);
+        println!("ascii: {:?}, chars: {:?}", ascii_time, chars_time);
+    }
+}
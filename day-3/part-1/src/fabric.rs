@@ -0,0 +1,140 @@
+use crate::Claim;
+use std::collections::HashMap;
+
+// Claims whose bounding box has at most this many square inches are counted
+// on a flat grid; anything larger falls back to a map of claimed inches only.
+const DENSE_LIMIT: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum Fabric {
+    Dense {
+        x_min: u32,
+        y_min: u32,
+        width: usize,
+        counts: Vec<u16>,
+    },
+    Sparse(HashMap<(u32, u32), u32>),
+}
+
+impl Fabric {
+    pub fn for_claims(claims: &[Claim]) -> Self {
+        let x_min = claims.iter().map(|c| c.x).min().unwrap_or(0);
+        let y_min = claims.iter().map(|c| c.y).min().unwrap_or(0);
+        let x_max = claims.iter().map(|c| c.x + c.width).max().unwrap_or(0);
+        let y_max = claims.iter().map(|c| c.y + c.height).max().unwrap_or(0);
+
+        let width = (x_max - x_min) as u64;
+        let height = (y_max - y_min) as u64;
+
+        if width * height <= DENSE_LIMIT {
+            Fabric::dense(x_min, y_min, width as usize, height as usize)
+        } else {
+            Fabric::sparse()
+        }
+    }
+
+    pub fn dense(x_min: u32, y_min: u32, width: usize, height: usize) -> Self {
+        Fabric::Dense {
+            x_min,
+            y_min,
+            width,
+            counts: vec![0; width * height],
+        }
+    }
+
+    pub fn sparse() -> Self {
+        Fabric::Sparse(HashMap::new())
+    }
+
+    pub fn claim(&mut self, claim: &Claim) {
+        let x_max = claim.x + claim.width;
+        let y_max = claim.y + claim.height;
+
+        match self {
+            Fabric::Dense {
+                x_min,
+                y_min,
+                width,
+                counts,
+            } => {
+                for y in claim.y..y_max {
+                    let row = (y - *y_min) as usize * *width;
+                    let start = row + (claim.x - *x_min) as usize;
+                    let end = row + (x_max - *x_min) as usize;
+                    for count in counts[start..end].iter_mut() {
+                        *count = count.saturating_add(1);
+                    }
+                }
+            }
+            Fabric::Sparse(cloth) => {
+                for x in claim.x..x_max {
+                    for y in claim.y..y_max {
+                        *cloth.entry((x, y)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn count(&self, x: u32, y: u32) -> u32 {
+        match self {
+            Fabric::Dense {
+                x_min,
+                y_min,
+                width,
+                counts,
+            } => {
+                if x < *x_min || y < *y_min || (x - *x_min) as usize >= *width {
+                    return 0;
+                }
+                let index = (y - *y_min) as usize * *width + (x - *x_min) as usize;
+                counts.get(index).map_or(0, |&count| count as u32)
+            }
+            Fabric::Sparse(cloth) => cloth.get(&(x, y)).copied().unwrap_or(0),
+        }
+    }
+
+    pub fn overlapping_sq_inches(&self) -> usize {
+        match self {
+            Fabric::Dense { counts, .. } => counts.iter().filter(|&&count| count > 1).count(),
+            Fabric::Sparse(cloth) => cloth.values().filter(|&&count| count > 1).count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(input: &str) -> Vec<Claim> {
+        input.lines().map(Claim::from).collect()
+    }
+
+    #[test]
+    fn backends_agree() {
+        let claims = claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#4 @ 2,2: 1x1");
+
+        let mut dense = Fabric::dense(1, 1, 6, 6);
+        let mut sparse = Fabric::sparse();
+        for claim in claims.iter() {
+            dense.claim(claim);
+            sparse.claim(claim);
+        }
+
+        assert_eq!(dense.overlapping_sq_inches(), 4);
+        assert_eq!(sparse.overlapping_sq_inches(), 4);
+        assert_eq!(dense.count(3, 3), 2);
+        assert_eq!(sparse.count(3, 3), 2);
+        assert_eq!(dense.count(0, 0), 0);
+        assert_eq!(sparse.count(0, 0), 0);
+    }
+
+    #[test]
+    fn selects_backend_from_bounding_box() {
+        let small = claims("#1 @ 100,100: 10x10\n#2 @ 900,900: 50x50");
+        assert!(matches!(Fabric::for_claims(&small), Fabric::Dense { .. }));
+
+        let spread = claims("#1 @ 0,0: 1x1\n#2 @ 1000000,1000000: 1x1");
+        assert!(matches!(Fabric::for_claims(&spread), Fabric::Sparse(_)));
+    }
+}
@@ -1,6 +1,20 @@
-use std::collections::HashMap;
+mod fabric;
+mod formats;
+mod report;
+mod sheet;
+mod sweep;
 
-#[derive(Debug)]
+use fabric::Fabric;
+use formats::Format;
+use formats::FormatError;
+use report::SortKey;
+use sheet::Policy;
+use sheet::Sheet;
+use std::env;
+use std::fmt;
+use std::fs;
+
+#[derive(Debug, Clone)]
 struct Claim {
     id: u32,
     x: u32,
@@ -11,7 +25,7 @@ struct Claim {
 
 impl From<&str> for Claim {
     fn from(string: &str) -> Self {
-        let parts: Vec<&str> = string.split(|c| c == '@' || c == ':').collect();
+        let parts: Vec<&str> = string.split(['@', ':']).collect();
 
         let id = parts[0]
             .trim()
@@ -31,33 +45,147 @@ impl From<&str> for Claim {
     }
 }
 
+impl fmt::Display for Claim {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} @ {},{}: {}x{}",
+            self.id, self.x, self.y, self.width, self.height
+        )
+    }
+}
+
 fn main() {
     let input = include_str!("./input.txt");
-    let overlaps = find_overlapping_sq_inches(input);
-    println!("{}", overlaps);
+
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("import") | Some("convert") => {
+            let path = match args.get(1) {
+                Some(path) => path,
+                None => {
+                    println!("usage: import <path> [clip] [<sheet width> <sheet height>] | convert <path> <claims|csv|json> [clip] [<sheet width> <sheet height>]");
+                    return;
+                }
+            };
+            let text = fs::read_to_string(path).expect("couldn't read claims");
+            let claims = match formats::read(&text, Format::from(path.as_str())) {
+                Ok(claims) => claims,
+                Err(error) => {
+                    println!("{}: {}", path, error);
+                    return;
+                }
+            };
+            let options = if args[0] == "convert" { 3 } else { 2 };
+            let (sheet, policy) = sheet_options(args.get(options..).unwrap_or_default());
+            let claims = on_sheet(&claims, sheet, policy);
+
+            if args[0] == "convert" {
+                let format = args
+                    .get(2)
+                    .map_or(Format::Claims, |f| Format::from(f.as_str()));
+                print!("{}", formats::write(&claims, format));
+                return;
+            }
+
+            println!("{}", claim_cloth(&claims).overlapping_sq_inches());
+        }
+        Some("sweep") => {
+            let claims = load_claims(input);
+            println!("{}", sweep::overlapping_area(&claims));
+        }
+        Some("report") => {
+            let claims = load_claims(input);
+            let mut report = report::contention_report(&claims, &claim_cloth(&claims));
+            let key = args
+                .get(2)
+                .map_or(SortKey::Id, |key| SortKey::from(key.as_str()));
+            report::sort_report(&mut report, key);
+
+            match args.get(1).map(String::as_str) {
+                Some("csv") => print!("{}", report::to_csv(&report)),
+                Some("json") => print!("{}", report::to_json(&report)),
+                _ => print!("{}", report::to_table(&report)),
+            }
+        }
+        _ => {
+            let overlaps = find_overlapping_sq_inches(input);
+            println!("{}", overlaps);
+        }
+    }
 }
 
-fn find_overlapping_sq_inches(input: &str) -> usize {
-    let mut cloth = HashMap::new();
+// The solvers read the puzzle input through here. Lines that don't parse,
+// and claims whose far edge overflows u32 or leaves the sheet, are reported
+// and skipped so they never reach the cloth.
+fn load_claims(input: &str) -> Vec<Claim> {
+    let sheet = Sheet::default();
+    let mut claims = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
 
-    for claim in input.lines().map(Claim::from) {
-        claim_cloth_area(&claim, &mut cloth);
+        let claim = formats::parse_claim(line)
+            .map_err(|message| {
+                FormatError {
+                    line: i + 1,
+                    message,
+                }
+                .to_string()
+            })
+            .and_then(|claim| match sheet.check(i + 1, &claim) {
+                Ok(()) => Ok(claim),
+                Err(error) => Err(error.to_string()),
+            });
+        match claim {
+            Ok(claim) => claims.push(claim),
+            Err(error) => eprintln!("warning: {}", error),
+        }
     }
 
-    cloth.values().filter(|&val| *val > 1).count()
+    claims
 }
 
-fn claim_cloth_area(claim: &Claim, cloth: &mut HashMap<(u32, u32), u32>) {
-    let x_min = claim.x;
-    let x_max = claim.x + claim.width;
-    let y_min = claim.y;
-    let y_max = claim.y + claim.height;
+fn on_sheet(claims: &[Claim], sheet: Sheet, policy: Policy) -> Vec<Claim> {
+    let (kept, errors) = sheet.validate(claims, policy);
+    for error in errors {
+        eprintln!("warning: {}", error);
+    }
+    kept
+}
 
-    for x in x_min..x_max {
-        for y in y_min..y_max {
-            *cloth.entry((x, y)).or_insert(0) += 1;
-        }
+// Reads `[clip] [<sheet width> <sheet height>]`, the sheet options taken by
+// `import` and `convert`.
+fn sheet_options(args: &[String]) -> (Sheet, Policy) {
+    let policy = match args.first().map(String::as_str) {
+        Some("clip") => Policy::Clip,
+        _ => Policy::Reject,
+    };
+    let numbers: Vec<u32> = args.iter().filter_map(|n| n.parse().ok()).collect();
+    let sheet = match *numbers.as_slice() {
+        [width, height] => Sheet::new(width, height),
+        _ => Sheet::default(),
+    };
+
+    (sheet, policy)
+}
+
+fn find_overlapping_sq_inches(input: &str) -> usize {
+    let claims = load_claims(input);
+    claim_cloth(&claims).overlapping_sq_inches()
+}
+
+fn claim_cloth(claims: &[Claim]) -> Fabric {
+    let mut cloth = Fabric::for_claims(claims);
+
+    for claim in claims.iter() {
+        cloth.claim(claim);
     }
+
+    cloth
 }
 
 #[cfg(test)]
@@ -74,4 +202,13 @@ mod tests {
         assert_eq!(claim.width, 5);
         assert_eq!(claim.height, 4);
     }
+
+    #[test]
+    fn claims_off_the_sheet_are_skipped() {
+        let input = "#1 @ 4294967290,4294967290: 10x10\n#2 @ 4294967295,4294967295: 5x5\n#x @ 0,0: 9x9\n#3 @ 0,0: 2x2\n#4 @ 1,1: 2x2";
+        assert_eq!(find_overlapping_sq_inches(input), 1);
+
+        let ids: Vec<u32> = load_claims(input).iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![3, 4]);
+    }
 }
@@ -0,0 +1,219 @@
+use crate::fabric::Fabric;
+use crate::Claim;
+use std::cmp::Reverse;
+
+#[derive(Debug, PartialEq)]
+pub struct Contention {
+    pub id: u32,
+    pub area: u64,
+    pub contested_area: u64,
+    pub overlapping_claims: usize,
+}
+
+impl Contention {
+    pub fn contested_fraction(&self) -> f64 {
+        if self.area == 0 {
+            return 0.0;
+        }
+        self.contested_area as f64 / self.area as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Id,
+    Area,
+    ContestedArea,
+    ContestedFraction,
+    OverlappingClaims,
+}
+
+impl From<&str> for SortKey {
+    fn from(string: &str) -> Self {
+        match string {
+            "area" => SortKey::Area,
+            "contested" => SortKey::ContestedArea,
+            "fraction" => SortKey::ContestedFraction,
+            "overlaps" => SortKey::OverlappingClaims,
+            _ => SortKey::Id,
+        }
+    }
+}
+
+// Contested inches are read back from the cloth; the number of distinct
+// claims overlapping each one needs the rectangles themselves, since the
+// cloth only knows how many claims cover an inch and not which.
+pub fn contention_report(claims: &[Claim], cloth: &Fabric) -> Vec<Contention> {
+    let mut overlapping_claims = vec![0; claims.len()];
+    for (i, a) in claims.iter().enumerate() {
+        for (j, b) in claims.iter().enumerate().skip(i + 1) {
+            if intersects(a, b) {
+                overlapping_claims[i] += 1;
+                overlapping_claims[j] += 1;
+            }
+        }
+    }
+
+    claims
+        .iter()
+        .zip(overlapping_claims)
+        .map(|(claim, overlapping_claims)| {
+            let mut contested_area = 0;
+            for x in claim.x..claim.x + claim.width {
+                for y in claim.y..claim.y + claim.height {
+                    if cloth.count(x, y) > 1 {
+                        contested_area += 1;
+                    }
+                }
+            }
+
+            Contention {
+                id: claim.id,
+                area: claim.width as u64 * claim.height as u64,
+                contested_area,
+                overlapping_claims,
+            }
+        })
+        .collect()
+}
+
+// Ids sort ascending; every other key puts the most contested claims first.
+pub fn sort_report(report: &mut [Contention], key: SortKey) {
+    match key {
+        SortKey::Id => report.sort_by_key(|c| c.id),
+        SortKey::Area => report.sort_by_key(|c| Reverse(c.area)),
+        SortKey::ContestedArea => report.sort_by_key(|c| Reverse(c.contested_area)),
+        SortKey::ContestedFraction => report.sort_by(|a, b| {
+            b.contested_fraction()
+                .partial_cmp(&a.contested_fraction())
+                .expect("contested fractions are never NaN")
+        }),
+        SortKey::OverlappingClaims => report.sort_by_key(|c| Reverse(c.overlapping_claims)),
+    }
+}
+
+pub fn to_csv(report: &[Contention]) -> String {
+    let mut csv = String::from("id,area,contested_area,contested_fraction,overlapping_claims\n");
+    for c in report {
+        csv.push_str(&format!(
+            "{},{},{},{:.4},{}\n",
+            c.id,
+            c.area,
+            c.contested_area,
+            c.contested_fraction(),
+            c.overlapping_claims
+        ));
+    }
+    csv
+}
+
+pub fn to_json(report: &[Contention]) -> String {
+    let rows: Vec<String> = report
+        .iter()
+        .map(|c| {
+            format!(
+                "  {{\"id\": {}, \"area\": {}, \"contested_area\": {}, \"contested_fraction\": {:.4}, \"overlapping_claims\": {}}}",
+                c.id,
+                c.area,
+                c.contested_area,
+                c.contested_fraction(),
+                c.overlapping_claims
+            )
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return String::from("[]\n");
+    }
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+pub fn to_table(report: &[Contention]) -> String {
+    let mut table = format!(
+        "{:>6} {:>6} {:>10} {:>9} {:>9}\n",
+        "id", "area", "contested", "fraction", "overlaps"
+    );
+    for c in report {
+        table.push_str(&format!(
+            "{:>6} {:>6} {:>10} {:>9.4} {:>9}\n",
+            c.id,
+            c.area,
+            c.contested_area,
+            c.contested_fraction(),
+            c.overlapping_claims
+        ));
+    }
+    table
+}
+
+fn intersects(a: &Claim, b: &Claim) -> bool {
+    let a_right = a.x as u64 + a.width as u64;
+    let a_bottom = a.y as u64 + a.height as u64;
+    let b_right = b.x as u64 + b.width as u64;
+    let b_bottom = b.y as u64 + b.height as u64;
+
+    (a.x as u64) < b_right
+        && (b.x as u64) < a_right
+        && (a.y as u64) < b_bottom
+        && (b.y as u64) < a_bottom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claim_cloth;
+
+    fn example() -> Vec<Contention> {
+        let claims: Vec<Claim> = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#4 @ 4,4: 2x2"
+            .lines()
+            .map(Claim::from)
+            .collect();
+        contention_report(&claims, &claim_cloth(&claims))
+    }
+
+    #[test]
+    fn report() {
+        let report = example();
+        let rows: Vec<(u32, u64, u64, usize)> = report
+            .iter()
+            .map(|c| (c.id, c.area, c.contested_area, c.overlapping_claims))
+            .collect();
+
+        assert_eq!(
+            rows,
+            vec![(1, 16, 5, 2), (2, 16, 5, 2), (3, 4, 1, 1), (4, 4, 4, 3)]
+        );
+        assert_eq!(report[3].contested_fraction(), 1.0);
+    }
+
+    #[test]
+    fn sorting() {
+        let mut report = example();
+
+        sort_report(&mut report, SortKey::ContestedFraction);
+        let ids: Vec<u32> = report.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![4, 1, 2, 3]);
+
+        sort_report(&mut report, SortKey::Id);
+        let ids: Vec<u32> = report.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn export() {
+        let report = example();
+
+        let csv = to_csv(&report[..1]);
+        assert_eq!(
+            csv,
+            "id,area,contested_area,contested_fraction,overlapping_claims\n1,16,5,0.3125,2\n"
+        );
+
+        let json = to_json(&report[2..3]);
+        assert_eq!(
+            json,
+            "[\n  {\"id\": 3, \"area\": 4, \"contested_area\": 1, \"contested_fraction\": 0.2500, \"overlapping_claims\": 1}\n]\n"
+        );
+        assert_eq!(to_json(&[]), "[]\n");
+    }
+}
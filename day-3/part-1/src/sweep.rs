@@ -0,0 +1,92 @@
+use crate::Claim;
+
+// Sweeps a vertical line across the compressed x coordinates of every claim
+// edge. Between two neighbouring edges the set of claims crossing the line is
+// fixed, so the doubly-claimed height of that slab is found once and
+// multiplied by its width. Edges are widened to u64 so claims reaching
+// u32::MAX don't overflow.
+pub fn overlapping_area(claims: &[Claim]) -> u64 {
+    let mut xs: Vec<u64> = claims
+        .iter()
+        .flat_map(|claim| vec![claim.x as u64, claim.x as u64 + claim.width as u64])
+        .collect();
+    xs.sort_unstable();
+    xs.dedup();
+
+    let mut area = 0;
+
+    for slab in xs.windows(2) {
+        let (left, right) = (slab[0], slab[1]);
+
+        let mut edges: Vec<(u64, i32)> = claims
+            .iter()
+            .filter(|claim| claim.x as u64 <= left && right <= claim.x as u64 + claim.width as u64)
+            .flat_map(|claim| {
+                vec![
+                    (claim.y as u64, 1),
+                    (claim.y as u64 + claim.height as u64, -1),
+                ]
+            })
+            .collect();
+        edges.sort_unstable();
+
+        let mut depth = 0;
+        let mut previous_y = 0;
+        let mut overlapping_height = 0;
+
+        for (y, delta) in edges {
+            if depth > 1 {
+                overlapping_height += y - previous_y;
+            }
+            depth += delta;
+            previous_y = y;
+        }
+
+        area += overlapping_height * (right - left);
+    }
+
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let claims: Vec<Claim> = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2"
+            .lines()
+            .map(Claim::from)
+            .collect();
+        assert_eq!(overlapping_area(&claims), 4);
+    }
+
+    #[test]
+    fn triple_overlap_counts_once() {
+        let claims: Vec<Claim> = "#1 @ 0,0: 3x3\n#2 @ 0,0: 3x3\n#3 @ 1,1: 3x3"
+            .lines()
+            .map(Claim::from)
+            .collect();
+        assert_eq!(overlapping_area(&claims), 9);
+    }
+
+    #[test]
+    fn coordinates_near_u32_max() {
+        let claims: Vec<Claim> =
+            "#1 @ 4294967290,4294967290: 10x10\n#2 @ 4294967295,4294967295: 5x5"
+                .lines()
+                .map(Claim::from)
+                .collect();
+        assert_eq!(overlapping_area(&claims), 25);
+    }
+
+    #[test]
+    fn agrees_with_cloth_map() {
+        let input = include_str!("./input.txt");
+        let claims: Vec<Claim> = input.lines().map(Claim::from).collect();
+        assert_eq!(
+            overlapping_area(&claims),
+            crate::find_overlapping_sq_inches(input) as u64
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use crate::Claim;
+use std::error::Error;
+use std::fmt;
+
+// A claim on an N-dimensional box, written `#id @ x,y,z: wxhxd` with as many
+// coordinates and sizes as the box has dimensions. All claims compared with
+// each other must have the same number of dimensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxClaim {
+    pub id: u32,
+    pub origin: Vec<u32>,
+    pub size: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BoxError {
+    Malformed {
+        line: usize,
+        message: String,
+    },
+    SizeMismatch {
+        line: usize,
+        id: u32,
+    },
+    MixedDimensions {
+        line: usize,
+        id: u32,
+        expected: usize,
+    },
+    VolumeOverflow,
+}
+
+impl fmt::Display for BoxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoxError::Malformed { line, message } => write!(f, "line {}: {}", line, message),
+            BoxError::SizeMismatch { line, id } => write!(
+                f,
+                "line {}: claim #{} has a different number of coordinates and sizes",
+                line, id
+            ),
+            BoxError::MixedDimensions { line, id, expected } => write!(
+                f,
+                "line {}: claim #{} doesn't have {} dimensions like the claims before it",
+                line, id, expected
+            ),
+            BoxError::VolumeOverflow => write!(f, "overlapping volume doesn't fit in u128"),
+        }
+    }
+}
+
+impl Error for BoxError {}
+
+impl BoxClaim {
+    pub fn parse(line: usize, string: &str) -> Result<Self, BoxError> {
+        let malformed = |message: String| BoxError::Malformed { line, message };
+        let numbers = |list: &str, separator: char| -> Result<Vec<u32>, BoxError> {
+            list.trim()
+                .split(separator)
+                .map(|n| {
+                    n.trim()
+                        .parse()
+                        .map_err(|_| malformed(format!("`{}` is not a number", n.trim())))
+                })
+                .collect()
+        };
+
+        let (id, rest) = string.split_once('@').ok_or_else(|| {
+            malformed(format!(
+                "expected `#<id> @ <coordinates>: <sizes>`, got `{}`",
+                string.trim()
+            ))
+        })?;
+        let (origin, size) = rest
+            .split_once(':')
+            .ok_or_else(|| malformed(String::from("missing `:` before the sizes")))?;
+
+        let id = id.trim();
+        let id = id.strip_prefix('#').unwrap_or(id);
+        let id = id
+            .parse()
+            .map_err(|_| malformed(format!("`{}` is not a number", id)))?;
+        let origin = numbers(origin, ',')?;
+        let size = numbers(size, 'x')?;
+
+        if origin.len() != size.len() {
+            return Err(BoxError::SizeMismatch { line, id });
+        }
+
+        Ok(BoxClaim { id, origin, size })
+    }
+}
+
+// Blank lines are skipped, but still count towards the line numbers.
+pub fn read(text: &str) -> Result<Vec<BoxClaim>, BoxError> {
+    let mut claims: Vec<BoxClaim> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let claim = BoxClaim::parse(i + 1, line)?;
+        if let Some(first) = claims.first() {
+            if claim.dimensions() != first.dimensions() {
+                return Err(BoxError::MixedDimensions {
+                    line: i + 1,
+                    id: claim.id,
+                    expected: first.dimensions(),
+                });
+            }
+        }
+        claims.push(claim);
+    }
+
+    Ok(claims)
+}
+
+// Claims of different dimensions can't be measured against each other. The
+// first claim sets the dimension; the error's line is the claim's position.
+pub fn check_dimensions(claims: &[BoxClaim]) -> Result<(), BoxError> {
+    let expected = match claims.first() {
+        Some(first) => first.dimensions(),
+        None => return Ok(()),
+    };
+
+    match claims
+        .iter()
+        .enumerate()
+        .find(|(_i, claim)| claim.dimensions() != expected)
+    {
+        Some((i, claim)) => Err(BoxError::MixedDimensions {
+            line: i + 1,
+            id: claim.id,
+            expected,
+        }),
+        None => Ok(()),
+    }
+}
+
+impl From<&Claim> for BoxClaim {
+    fn from(claim: &Claim) -> Self {
+        BoxClaim {
+            id: claim.id,
+            origin: vec![claim.x, claim.y],
+            size: vec![claim.width, claim.height],
+        }
+    }
+}
+
+impl BoxClaim {
+    pub fn dimensions(&self) -> usize {
+        self.origin.len()
+    }
+
+    fn start(&self, dim: usize) -> u64 {
+        self.origin[dim] as u64
+    }
+
+    fn end(&self, dim: usize) -> u64 {
+        self.origin[dim] as u64 + self.size[dim] as u64
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size.contains(&0)
+    }
+
+    pub fn overlaps(&self, other: &BoxClaim) -> bool {
+        self.dimensions() == other.dimensions()
+            && !self.is_empty()
+            && !other.is_empty()
+            && (0..self.dimensions())
+                .all(|dim| self.start(dim) < other.end(dim) && other.start(dim) < self.end(dim))
+    }
+}
+
+// The same coordinate compression as the 2D sweep, applied one dimension at
+// a time: each slab between neighbouring edges of the first dimension is
+// measured recursively over the remaining ones. Each dimension can span
+// 2^32 inches, so volumes are summed in u128 and checked for overflow.
+pub fn overlapping_volume(claims: &[BoxClaim]) -> Result<u128, BoxError> {
+    check_dimensions(claims)?;
+
+    let claims: Vec<&BoxClaim> = claims.iter().filter(|claim| !claim.is_empty()).collect();
+    match claims.first() {
+        Some(first) if first.dimensions() > 0 => {
+            overlap_measure(&claims, 0, first.dimensions()).ok_or(BoxError::VolumeOverflow)
+        }
+        _ => Ok(0),
+    }
+}
+
+fn overlap_measure(claims: &[&BoxClaim], dim: usize, dimensions: usize) -> Option<u128> {
+    if claims.len() < 2 {
+        return Some(0);
+    }
+
+    if dim + 1 == dimensions {
+        let mut edges: Vec<(u64, i32)> = claims
+            .iter()
+            .flat_map(|claim| vec![(claim.start(dim), 1), (claim.end(dim), -1)])
+            .collect();
+        edges.sort_unstable();
+
+        let mut depth = 0;
+        let mut previous = 0;
+        let mut length = 0;
+        for (edge, delta) in edges {
+            if depth > 1 {
+                length += edge - previous;
+            }
+            depth += delta;
+            previous = edge;
+        }
+        return Some(length as u128);
+    }
+
+    let mut edges: Vec<u64> = claims
+        .iter()
+        .flat_map(|claim| vec![claim.start(dim), claim.end(dim)])
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+
+    edges.windows(2).try_fold(0u128, |volume, slab| {
+        let crossing: Vec<&BoxClaim> = claims
+            .iter()
+            .copied()
+            .filter(|claim| claim.start(dim) <= slab[0] && slab[1] <= claim.end(dim))
+            .collect();
+        overlap_measure(&crossing, dim + 1, dimensions)?
+            .checked_mul((slab[1] - slab[0]) as u128)
+            .and_then(|slab_volume| volume.checked_add(slab_volume))
+    })
+}
+
+pub fn intact_ids(claims: &[BoxClaim]) -> Vec<u32> {
+    let mut order: Vec<usize> = (0..claims.len()).collect();
+    order.sort_by_key(|&i| claims[i].origin.first().copied().unwrap_or(0));
+
+    let mut overlapped = vec![false; claims.len()];
+    let mut active: Vec<usize> = Vec::new();
+
+    for &i in order.iter() {
+        let claim = &claims[i];
+        if claim.dimensions() > 0 {
+            active.retain(|&j| claims[j].end(0) > claim.start(0));
+        }
+
+        for &j in active.iter() {
+            if claim.overlaps(&claims[j]) {
+                overlapped[i] = true;
+                overlapped[j] = true;
+            }
+        }
+
+        active.push(i);
+    }
+
+    claims
+        .iter()
+        .zip(overlapped)
+        .filter(|(_claim, overlapped)| !overlapped)
+        .map(|(claim, _overlapped)| claim.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claims;
+
+    fn boxes(input: &str) -> Vec<BoxClaim> {
+        read(input).unwrap()
+    }
+
+    #[test]
+    fn parse_box_claim() {
+        let claim = BoxClaim::parse(1, "#7 @ 1,2,3: 4x5x6").unwrap();
+        assert_eq!(claim.id, 7);
+        assert_eq!(claim.origin, vec![1, 2, 3]);
+        assert_eq!(claim.size, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            BoxClaim::parse(3, "#7 @ 1,2,3: 4x5"),
+            Err(BoxError::SizeMismatch { line: 3, id: 7 })
+        );
+        assert_eq!(
+            BoxClaim::parse(1, "#7 @ 1,2: 4xfive")
+                .unwrap_err()
+                .to_string(),
+            "line 1: `five` is not a number"
+        );
+        assert!(BoxClaim::parse(1, "garbage").is_err());
+    }
+
+    #[test]
+    fn mixed_dimensions_are_rejected() {
+        let input = "#1 @ 0,0,0: 2x2x2\n\n#2 @ 1,1: 2x2";
+        assert_eq!(
+            read(input),
+            Err(BoxError::MixedDimensions {
+                line: 3,
+                id: 2,
+                expected: 3
+            })
+        );
+
+        let claims = vec![
+            BoxClaim::parse(1, "#1 @ 0,0,0: 2x2x2").unwrap(),
+            BoxClaim::parse(2, "#2 @ 1,1: 2x2").unwrap(),
+        ];
+        assert_eq!(
+            overlapping_volume(&claims),
+            Err(BoxError::MixedDimensions {
+                line: 2,
+                id: 2,
+                expected: 3
+            })
+        );
+    }
+
+    #[test]
+    fn two_dimensions_match_claims() {
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2";
+        let from_claims: Vec<BoxClaim> = claims(input).iter().map(BoxClaim::from).collect();
+
+        assert_eq!(from_claims, boxes(input));
+        assert_eq!(overlapping_volume(&from_claims), Ok(4));
+        assert_eq!(intact_ids(&from_claims), vec![3]);
+    }
+
+    #[test]
+    fn three_dimensions() {
+        let claims =
+            boxes("#1 @ 0,0,0: 2x2x2\n#2 @ 1,1,1: 2x2x2\n#3 @ 1,1,0: 1x1x4\n#4 @ 5,5,5: 1x1x1");
+        assert_eq!(overlapping_volume(&claims), Ok(3));
+        assert_eq!(intact_ids(&claims), vec![4]);
+    }
+
+    #[test]
+    fn huge_boxes_do_not_overflow() {
+        let side = u32::MAX as u128;
+        let claims = boxes(
+            "#1 @ 0,0,0: 4294967295x4294967295x4294967295\n#2 @ 0,0,0: 4294967295x4294967295x4294967295",
+        );
+        assert_eq!(overlapping_volume(&claims), Ok(side * side * side));
+
+        let claims = boxes("#1 @ 0,0,0,0,0: 4294967295x4294967295x4294967295x4294967295x4294967295\n#2 @ 0,0,0,0,0: 4294967295x4294967295x4294967295x4294967295x4294967295");
+        assert_eq!(overlapping_volume(&claims), Err(BoxError::VolumeOverflow));
+    }
+
+    #[test]
+    fn one_dimension() {
+        let claims = boxes("#1 @ 0: 5\n#2 @ 3: 5\n#3 @ 8: 1");
+        assert_eq!(overlapping_volume(&claims), Ok(2));
+        assert_eq!(intact_ids(&claims), vec![3]);
+    }
+
+    #[test]
+    fn agrees_with_two_dimensional_sweep() {
+        let claims = claims(include_str!("./input.txt"));
+        let boxes: Vec<BoxClaim> = claims.iter().map(BoxClaim::from).collect();
+
+        assert_eq!(intact_ids(&boxes), crate::sweep::intact_claim_ids(&claims));
+    }
+}
@@ -0,0 +1,392 @@
+use crate::Claim;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+// Claims whose bounding box has at most this many square inches are counted
+// on a flat grid; anything larger falls back to a map of claimed inches only.
+const DENSE_LIMIT: u64 = 16 * 1024 * 1024;
+
+// Every inch keeps how many claims cover it and the XOR of their slots, so
+// that whenever exactly one claim is left on an inch we know which one it is.
+#[derive(Debug)]
+enum Cells {
+    Dense {
+        x_min: u32,
+        y_min: u32,
+        width: usize,
+        height: usize,
+        counts: Vec<u16>,
+        owners: Vec<u32>,
+    },
+    Sparse(HashMap<(u32, u32), (u32, u32)>),
+}
+
+impl Cells {
+    fn get(&self, x: u32, y: u32) -> (u32, u32) {
+        match self {
+            Cells::Dense { counts, owners, .. } => match self.index(x, y) {
+                Some(i) => (counts[i] as u32, owners[i]),
+                None => (0, 0),
+            },
+            Cells::Sparse(cloth) => cloth.get(&(x, y)).copied().unwrap_or((0, 0)),
+        }
+    }
+
+    // Dense counts are u16, so an inch claimed more often than that moves the
+    // whole fabric to the sparse backend.
+    fn set(&mut self, x: u32, y: u32, count: u32, owner_slots: u32) {
+        if matches!(self, Cells::Dense { .. }) && u16::try_from(count).is_err() {
+            *self = self.to_sparse();
+        }
+        let index = self.index(x, y);
+
+        match self {
+            Cells::Dense { counts, owners, .. } => {
+                let i = index.expect("claim outside of the dense fabric");
+                counts[i] = u16::try_from(count).expect("dense counts fit in u16");
+                owners[i] = owner_slots;
+            }
+            Cells::Sparse(cloth) => {
+                if count == 0 {
+                    cloth.remove(&(x, y));
+                } else {
+                    cloth.insert((x, y), (count, owner_slots));
+                }
+            }
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        match self {
+            Cells::Dense {
+                x_min,
+                y_min,
+                width,
+                height,
+                ..
+            } => {
+                if x < *x_min || y < *y_min {
+                    return None;
+                }
+                let (col, row) = ((x - *x_min) as usize, (y - *y_min) as usize);
+                if col >= *width || row >= *height {
+                    return None;
+                }
+                Some(row * *width + col)
+            }
+            Cells::Sparse(_) => None,
+        }
+    }
+
+    fn fits(&self, claim: &Claim) -> bool {
+        match self {
+            Cells::Dense {
+                x_min,
+                y_min,
+                width,
+                height,
+                ..
+            } => {
+                claim.width == 0
+                    || claim.height == 0
+                    || (claim.x >= *x_min
+                        && claim.y >= *y_min
+                        && (claim.x - *x_min) as u64 + claim.width as u64 <= *width as u64
+                        && (claim.y - *y_min) as u64 + claim.height as u64 <= *height as u64)
+            }
+            Cells::Sparse(_) => true,
+        }
+    }
+
+    fn to_sparse(&self) -> Cells {
+        match self {
+            Cells::Dense {
+                x_min,
+                y_min,
+                width,
+                counts,
+                owners,
+                ..
+            } => {
+                let cloth = counts
+                    .iter()
+                    .zip(owners)
+                    .enumerate()
+                    .filter(|(_i, (&count, _owners))| count > 0)
+                    .map(|(i, (&count, &owners))| {
+                        let x = x_min + (i % width) as u32;
+                        let y = y_min + (i / width) as u32;
+                        ((x, y), (count as u32, owners))
+                    })
+                    .collect();
+                Cells::Sparse(cloth)
+            }
+            Cells::Sparse(cloth) => Cells::Sparse(cloth.clone()),
+        }
+    }
+}
+
+// Claims can be added and removed one at a time. Each added claim gets a slot
+// that identifies it for removal, and the fabric keeps the overlapping area
+// and the number of contested inches of every claim up to date as it goes.
+#[derive(Debug)]
+pub struct Fabric {
+    cells: Cells,
+    claims: Vec<Option<Claim>>,
+    contested: Vec<u64>,
+    overlapping: u64,
+}
+
+impl Fabric {
+    pub fn for_claims(claims: &[Claim]) -> Self {
+        let x_min = claims.iter().map(|c| c.x).min().unwrap_or(0);
+        let y_min = claims.iter().map(|c| c.y).min().unwrap_or(0);
+        let x_max = claims
+            .iter()
+            .map(|c| c.x as u64 + c.width as u64)
+            .max()
+            .unwrap_or(0);
+        let y_max = claims
+            .iter()
+            .map(|c| c.y as u64 + c.height as u64)
+            .max()
+            .unwrap_or(0);
+
+        let width = x_max.saturating_sub(x_min as u64);
+        let height = y_max.saturating_sub(y_min as u64);
+
+        if width
+            .checked_mul(height)
+            .is_some_and(|area| area <= DENSE_LIMIT)
+        {
+            Fabric::dense(x_min, y_min, width as usize, height as usize)
+        } else {
+            Fabric::sparse()
+        }
+    }
+
+    pub fn dense(x_min: u32, y_min: u32, width: usize, height: usize) -> Self {
+        Fabric::with_cells(Cells::Dense {
+            x_min,
+            y_min,
+            width,
+            height,
+            counts: vec![0; width * height],
+            owners: vec![0; width * height],
+        })
+    }
+
+    pub fn sparse() -> Self {
+        Fabric::with_cells(Cells::Sparse(HashMap::new()))
+    }
+
+    fn with_cells(cells: Cells) -> Self {
+        Fabric {
+            cells,
+            claims: Vec::new(),
+            contested: Vec::new(),
+            overlapping: 0,
+        }
+    }
+
+    // A dense fabric that can't hold the claim is moved to the sparse
+    // backend first rather than dropping the inches that fall outside it.
+    pub fn claim(&mut self, claim: &Claim) -> usize {
+        if !self.cells.fits(claim) {
+            self.cells = self.cells.to_sparse();
+        }
+
+        let slot = self.claims.len();
+        self.claims.push(Some(claim.clone()));
+        self.contested.push(0);
+
+        for (x, y) in inches(claim) {
+            let (count, owners) = self.cells.get(x, y);
+            match count {
+                0 => {}
+                1 => {
+                    self.contested[owners as usize] += 1;
+                    self.contested[slot] += 1;
+                    self.overlapping += 1;
+                }
+                _ => self.contested[slot] += 1,
+            }
+            self.cells.set(x, y, count + 1, owners ^ slot as u32);
+        }
+
+        slot
+    }
+
+    pub fn unclaim(&mut self, slot: usize) -> Option<Claim> {
+        let claim = self.claims.get_mut(slot)?.take()?;
+
+        for (x, y) in inches(&claim) {
+            let (count, owners) = self.cells.get(x, y);
+            let owners = owners ^ slot as u32;
+            match count {
+                2 => {
+                    self.contested[owners as usize] -= 1;
+                    self.contested[slot] -= 1;
+                    self.overlapping -= 1;
+                }
+                c if c > 2 => self.contested[slot] -= 1,
+                _ => {}
+            }
+            self.cells.set(x, y, count - 1, owners);
+        }
+
+        Some(claim)
+    }
+
+    pub fn slot_of(&self, id: u32) -> Option<usize> {
+        self.claims
+            .iter()
+            .position(|claim| claim.as_ref().is_some_and(|claim| claim.id == id))
+    }
+
+    pub fn overlapping_sq_inches(&self) -> u64 {
+        self.overlapping
+    }
+
+    pub fn intact_ids(&self) -> Vec<u32> {
+        self.claims
+            .iter()
+            .zip(self.contested.iter())
+            .filter_map(|(claim, &contested)| match claim {
+                Some(claim) if contested == 0 => Some(claim.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn count(&self, x: u32, y: u32) -> u32 {
+        self.cells.get(x, y).0
+    }
+
+    pub fn has_overlaps(&self, claim: &Claim) -> bool {
+        inches(claim).any(|(x, y)| self.count(x, y) > 1)
+    }
+}
+
+// Claims are checked against the sheet before they reach the cloth; should
+// one still run past u32::MAX, the inches beyond it don't exist on the cloth
+// and are left out rather than overflowing.
+fn inches(claim: &Claim) -> impl Iterator<Item = (u32, u32)> {
+    let end = |start: u32, length: u32| (start as u64 + length as u64).min(u32::MAX as u64 + 1);
+    let (x_min, x_max) = (claim.x as u64, end(claim.x, claim.width));
+    let (y_min, y_max) = (claim.y as u64, end(claim.y, claim.height));
+    (y_min..y_max).flat_map(move |y| (x_min..x_max).map(move |x| (x as u32, y as u32)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claims;
+
+    #[test]
+    fn backends_agree() {
+        let claims = claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#4 @ 2,2: 1x1");
+
+        let mut dense = Fabric::dense(1, 1, 6, 6);
+        let mut sparse = Fabric::sparse();
+        for claim in claims.iter() {
+            dense.claim(claim);
+            sparse.claim(claim);
+        }
+
+        for (i, claim) in claims.iter().enumerate() {
+            assert_eq!(dense.has_overlaps(claim), i < 2, "claim {}", claim.id);
+            assert_eq!(sparse.has_overlaps(claim), i < 2, "claim {}", claim.id);
+        }
+        assert_eq!(dense.count(3, 3), 2);
+        assert_eq!(sparse.count(3, 3), 2);
+        assert_eq!(dense.count(0, 0), 0);
+        assert_eq!(sparse.count(0, 0), 0);
+    }
+
+    #[test]
+    fn selects_backend_from_bounding_box() {
+        let small = claims("#1 @ 100,100: 10x10\n#2 @ 900,900: 50x50");
+        assert!(matches!(
+            Fabric::for_claims(&small).cells,
+            Cells::Dense { .. }
+        ));
+
+        let spread = claims("#1 @ 0,0: 1x1\n#2 @ 1000000,1000000: 1x1");
+        assert!(matches!(
+            Fabric::for_claims(&spread).cells,
+            Cells::Sparse(_)
+        ));
+    }
+
+    #[test]
+    fn add_and_remove_claims() {
+        let claims = claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#4 @ 4,4: 2x2");
+
+        for mut cloth in [Fabric::dense(0, 0, 8, 8), Fabric::sparse()] {
+            let slots: Vec<usize> = claims[..3].iter().map(|c| cloth.claim(c)).collect();
+            assert_eq!(cloth.overlapping_sq_inches(), 4);
+            assert_eq!(cloth.intact_ids(), vec![3]);
+
+            let four = cloth.claim(&claims[3]);
+            assert_eq!(cloth.overlapping_sq_inches(), 7);
+            assert_eq!(cloth.intact_ids(), vec![]);
+
+            assert_eq!(cloth.unclaim(slots[0]).map(|c| c.id), Some(1));
+            assert_eq!(cloth.unclaim(slots[0]).map(|c| c.id), None);
+            assert_eq!(cloth.overlapping_sq_inches(), 3);
+            assert_eq!(cloth.intact_ids(), vec![]);
+
+            cloth.unclaim(four);
+            assert_eq!(cloth.overlapping_sq_inches(), 0);
+            assert_eq!(cloth.intact_ids(), vec![2, 3]);
+            assert_eq!(cloth.slot_of(2), Some(slots[1]));
+            assert_eq!(cloth.slot_of(1), None);
+        }
+    }
+
+    #[test]
+    fn dense_moves_to_sparse_when_outgrown() {
+        let claims = claims("#1 @ 0,0: 2x2\n#2 @ 1,1: 5x5");
+        let mut cloth = Fabric::dense(0, 0, 2, 2);
+
+        cloth.claim(&claims[0]);
+        cloth.claim(&claims[1]);
+
+        assert!(matches!(cloth.cells, Cells::Sparse(_)));
+        assert_eq!(cloth.count(1, 1), 2);
+        assert_eq!(cloth.count(5, 5), 1);
+        assert_eq!(cloth.overlapping_sq_inches(), 1);
+    }
+
+    #[test]
+    fn dense_moves_to_sparse_when_counts_outgrow_u16() {
+        let claim = Claim::from("#1 @ 0,0: 1x1");
+        let mut cloth = Fabric::dense(0, 0, 1, 1);
+
+        for _ in 0..u16::MAX {
+            cloth.claim(&claim);
+        }
+        assert!(matches!(cloth.cells, Cells::Dense { .. }));
+
+        let last = cloth.claim(&claim);
+        assert!(matches!(cloth.cells, Cells::Sparse(_)));
+        assert_eq!(cloth.count(0, 0), u16::MAX as u32 + 1);
+
+        cloth.unclaim(last);
+        assert_eq!(cloth.count(0, 0), u16::MAX as u32);
+        assert_eq!(cloth.overlapping_sq_inches(), 1);
+    }
+
+    #[test]
+    fn claims_past_u32_max_do_not_overflow() {
+        let claims = claims("#1 @ 4294967295,0: 2x1\n#2 @ 4294967294,0: 2x1");
+        let mut cloth = Fabric::for_claims(&claims);
+        for claim in claims.iter() {
+            cloth.claim(claim);
+        }
+
+        assert_eq!(cloth.count(u32::MAX, 0), 2);
+        assert_eq!(cloth.overlapping_sq_inches(), 1);
+    }
+}
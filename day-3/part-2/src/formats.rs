@@ -0,0 +1,418 @@
+use crate::Claim;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Claims,
+    Csv,
+    Json,
+}
+
+// Accepts either a format name or a path, in which case the extension picks
+// the format. Anything unrecognised is read as the puzzle's own claim lines.
+impl From<&str> for Format {
+    fn from(string: &str) -> Self {
+        let name = string.rsplit('.').next().unwrap_or(string);
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Format::Csv,
+            "json" => Format::Json,
+            _ => Format::Claims,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FormatError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for FormatError {}
+
+const FIELDS: [&str; 5] = ["id", "x", "y", "width", "height"];
+
+pub fn read(text: &str, format: Format) -> Result<Vec<Claim>, FormatError> {
+    match format {
+        Format::Claims => from_claims(text),
+        Format::Csv => from_csv(text),
+        Format::Json => from_json(text),
+    }
+}
+
+pub fn write(claims: &[Claim], format: Format) -> String {
+    match format {
+        Format::Claims => claims.iter().map(|claim| format!("{}\n", claim)).collect(),
+        Format::Csv => to_csv(claims),
+        Format::Json => to_json(claims),
+    }
+}
+
+pub fn from_claims(text: &str) -> Result<Vec<Claim>, FormatError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_i, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_claim(line).map_err(|message| FormatError {
+                line: i + 1,
+                message,
+            })
+        })
+        .collect()
+}
+
+// Unlike `Claim::from`, which reads anything it can't parse as 0, this
+// insists on the whole `#id @ x,y: wxh` shape. The leading `#` is optional.
+pub fn parse_claim(line: &str) -> Result<Claim, String> {
+    let malformed = || {
+        format!(
+            "expected `#<id> @ <x>,<y>: <width>x<height>`, got `{}`",
+            line.trim()
+        )
+    };
+
+    let (id, rest) = line.split_once('@').ok_or_else(malformed)?;
+    let (coords, size) = rest.split_once(':').ok_or_else(malformed)?;
+    let (x, y) = coords.split_once(',').ok_or_else(malformed)?;
+    let (width, height) = size.split_once('x').ok_or_else(malformed)?;
+    let id = id.trim();
+    let id = id.strip_prefix('#').unwrap_or(id);
+
+    let mut values = [0; 5];
+    for (value, text) in values.iter_mut().zip([id, x, y, width, height]) {
+        let text = text.trim();
+        *value = text
+            .parse()
+            .map_err(|_| format!("`{}` is not a number", text))?;
+    }
+
+    Ok(claim_from_values(values))
+}
+
+pub fn to_csv(claims: &[Claim]) -> String {
+    let mut csv = FIELDS.join(",");
+    csv.push('\n');
+    for claim in claims {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            claim.id, claim.x, claim.y, claim.width, claim.height
+        ));
+    }
+    csv
+}
+
+// The header row is optional, but when present it may list the columns in
+// any order.
+pub fn from_csv(text: &str) -> Result<Vec<Claim>, FormatError> {
+    let mut columns: Vec<usize> = (0..FIELDS.len()).collect();
+    let mut claims = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if i == 0 && cells.iter().any(|cell| cell.parse::<u32>().is_err()) {
+            columns = FIELDS
+                .iter()
+                .map(|field| {
+                    cells
+                        .iter()
+                        .position(|cell| cell == field)
+                        .ok_or(FormatError {
+                            line: 1,
+                            message: format!("missing column `{}`", field),
+                        })
+                })
+                .collect::<Result<_, _>>()?;
+            continue;
+        }
+
+        let mut values = [0; 5];
+        for (value, &column) in values.iter_mut().zip(columns.iter()) {
+            let cell = cells.get(column).ok_or(FormatError {
+                line: i + 1,
+                message: format!("expected at least {} columns", column + 1),
+            })?;
+            *value = cell.parse().map_err(|_| FormatError {
+                line: i + 1,
+                message: format!("`{}` is not a number", cell),
+            })?;
+        }
+
+        claims.push(claim_from_values(values));
+    }
+
+    Ok(claims)
+}
+
+pub fn to_json(claims: &[Claim]) -> String {
+    let rows: Vec<String> = claims
+        .iter()
+        .map(|claim| {
+            format!(
+                "  {{\"id\": {}, \"x\": {}, \"y\": {}, \"width\": {}, \"height\": {}}}",
+                claim.id, claim.x, claim.y, claim.width, claim.height
+            )
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return String::from("[]\n");
+    }
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+// Only what claims need: an array of flat objects whose values are all
+// non-negative integers. Keys other than the claim's fields are rejected.
+pub fn from_json(text: &str) -> Result<Vec<Claim>, FormatError> {
+    let mut parser = JsonParser {
+        chars: text.chars().peekable(),
+        line: 1,
+    };
+    let mut claims = Vec::new();
+
+    parser.expect('[')?;
+    if parser.peek() == Some(']') {
+        parser.expect(']')?;
+        return parser.end(claims);
+    }
+
+    loop {
+        parser.expect('{')?;
+        let mut values = [None; 5];
+
+        loop {
+            let key = parser.string()?;
+            let field = FIELDS
+                .iter()
+                .position(|&field| field == key)
+                .ok_or_else(|| parser.error(format!("unknown field `{}`", key)))?;
+            parser.expect(':')?;
+            values[field] = Some(parser.number()?);
+
+            match parser.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => return Err(parser.error(String::from("expected `,` or `}`"))),
+            }
+        }
+
+        let mut claim_values = [0; 5];
+        for (field, value) in values.iter().enumerate() {
+            claim_values[field] =
+                value.ok_or_else(|| parser.error(format!("missing field `{}`", FIELDS[field])))?;
+        }
+        claims.push(claim_from_values(claim_values));
+
+        match parser.next() {
+            Some(',') => continue,
+            Some(']') => return parser.end(claims),
+            _ => return Err(parser.error(String::from("expected `,` or `]`"))),
+        }
+    }
+}
+
+fn claim_from_values([id, x, y, width, height]: [u32; 5]) -> Claim {
+    Claim {
+        id,
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+struct JsonParser<I: Iterator<Item = char>> {
+    chars: std::iter::Peekable<I>,
+    line: usize,
+}
+
+impl<I: Iterator<Item = char>> JsonParser<I> {
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), FormatError> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(self.error(format!("expected `{}`", expected))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, FormatError> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(string),
+                Some('\n') | None => return Err(self.error(String::from("unterminated string"))),
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<u32, FormatError> {
+        self.skip_whitespace();
+        let mut digits = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            digits.push(c);
+            self.chars.next();
+        }
+
+        digits
+            .parse()
+            .map_err(|_| self.error(String::from("expected a non-negative integer")))
+    }
+
+    fn end(&mut self, claims: Vec<Claim>) -> Result<Vec<Claim>, FormatError> {
+        match self.next() {
+            None => Ok(claims),
+            Some(_) => Err(self.error(String::from("unexpected text after the claims"))),
+        }
+    }
+
+    fn error(&self, message: String) -> FormatError {
+        FormatError {
+            line: self.line,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n";
+
+    fn ids_and_sizes(claims: &[Claim]) -> Vec<(u32, u32, u32, u32, u32)> {
+        claims
+            .iter()
+            .map(|c| (c.id, c.x, c.y, c.width, c.height))
+            .collect()
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let claims = read(EXAMPLE, Format::Claims).unwrap();
+        assert_eq!(write(&claims, Format::Claims), EXAMPLE);
+    }
+
+    #[test]
+    fn claims_errors() {
+        assert_eq!(
+            read("#1 @ 1,3: 4x4\ngarbage line\n", Format::Claims)
+                .unwrap_err()
+                .to_string(),
+            "line 2: expected `#<id> @ <x>,<y>: <width>x<height>`, got `garbage line`"
+        );
+        assert_eq!(
+            read("\n#1 @ 1,3: 4xfour\n", Format::Claims).unwrap_err(),
+            FormatError {
+                line: 2,
+                message: String::from("`four` is not a number"),
+            }
+        );
+        assert_eq!(
+            ids_and_sizes(&read("7 @ 1,2: 3x4", Format::Claims).unwrap()),
+            vec![(7, 1, 2, 3, 4)]
+        );
+    }
+
+    #[test]
+    fn csv_round_trips() {
+        let claims = read(EXAMPLE, Format::Claims).unwrap();
+        let csv = to_csv(&claims);
+        assert!(csv.starts_with("id,x,y,width,height\n1,1,3,4,4\n"));
+        assert_eq!(
+            ids_and_sizes(&from_csv(&csv).unwrap()),
+            ids_and_sizes(&claims)
+        );
+    }
+
+    #[test]
+    fn csv_columns_in_any_order() {
+        let claims = from_csv("width,height,id,y,x\n4,4,1,3,1\n").unwrap();
+        assert_eq!(ids_and_sizes(&claims), vec![(1, 1, 3, 4, 4)]);
+
+        let headerless = from_csv("7,1,2,3,4\n").unwrap();
+        assert_eq!(ids_and_sizes(&headerless), vec![(7, 1, 2, 3, 4)]);
+    }
+
+    #[test]
+    fn csv_errors() {
+        assert_eq!(
+            from_csv("id,x,y,width\n").unwrap_err().to_string(),
+            "line 1: missing column `height`"
+        );
+        assert_eq!(
+            from_csv("id,x,y,width,height\n1,2,3,4\n").unwrap_err().line,
+            2
+        );
+        assert_eq!(
+            from_csv("id,x,y,width,height\n1,2,3,4,-5\n")
+                .unwrap_err()
+                .message,
+            "`-5` is not a number"
+        );
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let claims = read(EXAMPLE, Format::Claims).unwrap();
+        let json = to_json(&claims);
+        assert_eq!(
+            ids_and_sizes(&from_json(&json).unwrap()),
+            ids_and_sizes(&claims)
+        );
+        assert!(from_json(&to_json(&[])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn json_errors() {
+        assert_eq!(
+            from_json("[\n  {\"id\": 1, \"x\": 1, \"y\": 1, \"width\": 1}\n]")
+                .unwrap_err()
+                .to_string(),
+            "line 2: missing field `height`"
+        );
+        assert!(from_json("[{\"id\": 1, \"depth\": 2}]").is_err());
+        assert!(from_json("[] trailing").is_err());
+    }
+
+    #[test]
+    fn format_from_name_or_path() {
+        assert_eq!(Format::from("csv"), Format::Csv);
+        assert_eq!(Format::from("claims.JSON"), Format::Json);
+        assert_eq!(Format::from("src/input.txt"), Format::Claims);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::sweep;
+use crate::Claim;
+
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+    pub id: u32,
+    pub area: u64,
+}
+
+#[derive(Debug)]
+pub struct OverlapGraph<'a> {
+    claims: &'a [Claim],
+    neighbours: Vec<Vec<(usize, u64)>>,
+}
+
+impl<'a> OverlapGraph<'a> {
+    pub fn new(claims: &'a [Claim]) -> Self {
+        let mut neighbours = vec![Vec::new(); claims.len()];
+
+        for (a, b) in sweep::overlapping_pairs(claims) {
+            let area = overlap_area(&claims[a], &claims[b]);
+            neighbours[a].push((b, area));
+            neighbours[b].push((a, area));
+        }
+
+        OverlapGraph { claims, neighbours }
+    }
+
+    pub fn conflicts(&self, id: u32) -> Option<Vec<Conflict>> {
+        let index = self.index_of(id)?;
+
+        Some(
+            self.neighbours[index]
+                .iter()
+                .map(|&(other, area)| Conflict {
+                    id: self.claims[other].id,
+                    area,
+                })
+                .collect(),
+        )
+    }
+
+    pub fn pairs(&self) -> Vec<(u32, u32, u64)> {
+        self.neighbours
+            .iter()
+            .enumerate()
+            .flat_map(|(a, others)| {
+                others
+                    .iter()
+                    .filter(move |&&(b, _area)| a < b)
+                    .map(move |&(b, area)| (self.claims[a].id, self.claims[b].id, area))
+            })
+            .collect()
+    }
+
+    // Claims that overlap nothing form components of their own.
+    pub fn components(&self) -> Vec<Vec<u32>> {
+        let mut visited = vec![false; self.claims.len()];
+        let mut components = Vec::new();
+
+        for start in 0..self.claims.len() {
+            if visited[start] {
+                continue;
+            }
+
+            visited[start] = true;
+            let mut stack = vec![start];
+            let mut component = Vec::new();
+
+            while let Some(curr) = stack.pop() {
+                component.push(self.claims[curr].id);
+                for &(next, _area) in self.neighbours[curr].iter() {
+                    if !visited[next] {
+                        visited[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    pub fn component_of(&self, id: u32) -> Option<Vec<u32>> {
+        self.components()
+            .into_iter()
+            .find(|component| component.contains(&id))
+    }
+
+    fn index_of(&self, id: u32) -> Option<usize> {
+        self.claims.iter().position(|claim| claim.id == id)
+    }
+}
+
+pub fn overlap_area(a: &Claim, b: &Claim) -> u64 {
+    let span = |start_a: u32, len_a: u32, start_b: u32, len_b: u32| {
+        let start = start_a.max(start_b) as u64;
+        let end = (start_a as u64 + len_a as u64).min(start_b as u64 + len_b as u64);
+        end.saturating_sub(start)
+    };
+
+    span(a.x, a.width, b.x, b.width) * span(a.y, a.height, b.y, b.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claims;
+
+    #[test]
+    fn example() {
+        let claims = claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2");
+        let graph = OverlapGraph::new(&claims);
+
+        assert_eq!(graph.conflicts(1), Some(vec![Conflict { id: 2, area: 4 }]));
+        assert_eq!(graph.conflicts(3), Some(vec![]));
+        assert_eq!(graph.conflicts(4), None);
+        assert_eq!(graph.pairs(), vec![(1, 2, 4)]);
+        assert_eq!(graph.components(), vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn components_follow_chains() {
+        let claims =
+            claims("#1 @ 0,0: 2x2\n#2 @ 10,10: 2x2\n#3 @ 1,1: 2x2\n#4 @ 2,2: 3x1\n#5 @ 11,11: 1x1");
+        let graph = OverlapGraph::new(&claims);
+
+        assert_eq!(graph.components(), vec![vec![1, 3, 4], vec![2, 5]]);
+        assert_eq!(graph.component_of(4), Some(vec![1, 3, 4]));
+        assert_eq!(graph.conflicts(3).map(|c| c.len()), Some(2));
+    }
+
+    #[test]
+    fn overlap_area_of_disjoint_claims() {
+        let claims = claims("#1 @ 0,0: 2x2\n#2 @ 2,0: 2x2");
+        assert_eq!(overlap_area(&claims[0], &claims[1]), 0);
+    }
+}
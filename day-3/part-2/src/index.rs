@@ -0,0 +1,200 @@
+use crate::Claim;
+
+const NODE_CAPACITY: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x_min: u64,
+    y_min: u64,
+    x_max: u64,
+    y_max: u64,
+}
+
+impl Rect {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Rect {
+            x_min: x as u64,
+            y_min: y as u64,
+            x_max: x as u64 + width as u64,
+            y_max: y as u64 + height as u64,
+        }
+    }
+
+    fn intersects(&self, other: &Rect) -> bool {
+        self.x_min < other.x_max
+            && other.x_min < self.x_max
+            && self.y_min < other.y_max
+            && other.y_min < self.y_max
+    }
+
+    fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            x_min: self.x_min.min(other.x_min),
+            y_min: self.y_min.min(other.y_min),
+            x_max: self.x_max.max(other.x_max),
+            y_max: self.y_max.max(other.y_max),
+        }
+    }
+
+    fn centre(&self) -> (u64, u64) {
+        ((self.x_min + self.x_max) / 2, (self.y_min + self.y_max) / 2)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Leaf(Vec<(Rect, usize)>),
+    Branch(Vec<(Rect, Node)>),
+}
+
+// A static R-tree, bulk loaded with sort-tile-recursive packing so that
+// sibling nodes cover compact, mostly disjoint areas of the sheet.
+#[derive(Debug)]
+pub struct ClaimIndex<'a> {
+    claims: &'a [Claim],
+    root: Option<Node>,
+}
+
+impl<'a> ClaimIndex<'a> {
+    pub fn new(claims: &'a [Claim]) -> Self {
+        let entries: Vec<(Rect, usize)> = claims
+            .iter()
+            .enumerate()
+            .filter(|(_i, claim)| claim.width > 0 && claim.height > 0)
+            .map(|(i, claim)| (Rect::new(claim.x, claim.y, claim.width, claim.height), i))
+            .collect();
+
+        if entries.is_empty() {
+            return ClaimIndex { claims, root: None };
+        }
+
+        let mut level: Vec<(Rect, Node)> = pack(entries)
+            .into_iter()
+            .map(|(rect, items)| (rect, Node::Leaf(items)))
+            .collect();
+
+        while level.len() > 1 {
+            level = pack(level)
+                .into_iter()
+                .map(|(rect, children)| (rect, Node::Branch(children)))
+                .collect();
+        }
+
+        ClaimIndex {
+            claims,
+            root: level.pop().map(|(_rect, node)| node),
+        }
+    }
+
+    pub fn covering(&self, x: u32, y: u32) -> Vec<u32> {
+        self.intersecting(x, y, 1, 1)
+    }
+
+    pub fn intersecting(&self, x: u32, y: u32, width: u32, height: u32) -> Vec<u32> {
+        let query = Rect::new(x, y, width, height);
+        let mut found = Vec::new();
+
+        if let Some(root) = &self.root {
+            search(root, &query, &mut found);
+        }
+
+        found.sort_unstable();
+        found.into_iter().map(|i| self.claims[i].id).collect()
+    }
+}
+
+fn search(node: &Node, query: &Rect, found: &mut Vec<usize>) {
+    match node {
+        Node::Leaf(items) => {
+            for (rect, i) in items {
+                if rect.intersects(query) {
+                    found.push(*i);
+                }
+            }
+        }
+        Node::Branch(children) => {
+            for (rect, child) in children {
+                if rect.intersects(query) {
+                    search(child, query, found);
+                }
+            }
+        }
+    }
+}
+
+fn pack<T>(mut items: Vec<(Rect, T)>) -> Vec<(Rect, Vec<(Rect, T)>)> {
+    let node_count = items.len().div_ceil(NODE_CAPACITY);
+    let slice_count = (node_count as f64).sqrt().ceil() as usize;
+    let slice_len = slice_count * NODE_CAPACITY;
+
+    items.sort_by_key(|(rect, _item)| rect.centre().0);
+
+    let mut nodes = Vec::with_capacity(node_count);
+    let mut items = items.into_iter().peekable();
+
+    while items.peek().is_some() {
+        let mut slice: Vec<(Rect, T)> = items.by_ref().take(slice_len).collect();
+        slice.sort_by_key(|(rect, _item)| rect.centre().1);
+
+        let mut slice = slice.into_iter().peekable();
+        while slice.peek().is_some() {
+            let group: Vec<(Rect, T)> = slice.by_ref().take(NODE_CAPACITY).collect();
+            let bounds = group
+                .iter()
+                .skip(1)
+                .fold(group[0].0, |bounds, (rect, _item)| bounds.union(rect));
+            nodes.push((bounds, group));
+        }
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claims;
+
+    #[test]
+    fn example() {
+        let claims = claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2");
+        let index = ClaimIndex::new(&claims);
+
+        assert_eq!(index.covering(3, 3), vec![1, 2]);
+        assert_eq!(index.covering(6, 6), vec![3]);
+        assert_eq!(index.covering(0, 0), vec![]);
+        assert_eq!(index.intersecting(4, 4, 2, 2), vec![1, 2, 3]);
+        assert_eq!(index.intersecting(7, 0, 10, 10), vec![]);
+    }
+
+    #[test]
+    fn empty_index() {
+        let claims = claims("#1 @ 1,1: 0x4");
+        assert_eq!(ClaimIndex::new(&claims).covering(1, 1), vec![]);
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let claims = claims(include_str!("./input.txt"));
+        let index = ClaimIndex::new(&claims);
+        let query = |x: u32, y: u32, w: u32, h: u32| -> Vec<u32> {
+            let rect = Rect::new(x, y, w, h);
+            claims
+                .iter()
+                .filter(|c| Rect::new(c.x, c.y, c.width, c.height).intersects(&rect))
+                .map(|c| c.id)
+                .collect()
+        };
+
+        for &(x, y, w, h) in [
+            (0, 0, 1, 1),
+            (500, 500, 1, 1),
+            (123, 456, 40, 3),
+            (0, 0, 1000, 1000),
+        ]
+        .iter()
+        {
+            assert_eq!(index.intersecting(x, y, w, h), query(x, y, w, h));
+        }
+    }
+}
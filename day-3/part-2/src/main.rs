@@ -1,6 +1,27 @@
-use std::collections::HashMap;
+mod boxes;
+mod fabric;
+mod formats;
+mod graph;
+mod index;
+mod placement;
+mod render;
+mod sheet;
+mod sweep;
 
-#[derive(Debug)]
+use fabric::Fabric;
+use formats::Format;
+use formats::FormatError;
+use graph::OverlapGraph;
+use index::ClaimIndex;
+use sheet::Policy;
+use sheet::Sheet;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+
+#[derive(Debug, Clone)]
 struct Claim {
     id: u32,
     x: u32,
@@ -11,7 +32,7 @@ struct Claim {
 
 impl From<&str> for Claim {
     fn from(string: &str) -> Self {
-        let parts: Vec<&str> = string.split(|c| c == '@' || c == ':').collect();
+        let parts: Vec<&str> = string.split(['@', ':']).collect();
 
         let id = parts[0]
             .trim()
@@ -31,65 +52,356 @@ impl From<&str> for Claim {
     }
 }
 
+impl fmt::Display for Claim {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} @ {},{}: {}x{}",
+            self.id, self.x, self.y, self.width, self.height
+        )
+    }
+}
+
 fn main() {
     let input = include_str!("./input.txt");
-    let id = find_non_overlapping_claim_id(input);
-    println!("{}", id);
-}
 
-fn find_non_overlapping_claim_id(input: &str) -> u32 {
-    let mut cloth = HashMap::new();
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    let claims: Vec<Claim> = input.lines().map(Claim::from).collect();
+    match args.first().map(String::as_str) {
+        Some("import") | Some("convert") => {
+            let path = match args.get(1) {
+                Some(path) => path,
+                None => {
+                    println!("usage: import <path> [clip] [<sheet width> <sheet height>] | convert <path> <claims|csv|json> [clip] [<sheet width> <sheet height>]");
+                    return;
+                }
+            };
+            let text = fs::read_to_string(path).expect("couldn't read claims");
+            let claims = match formats::read(&text, Format::from(path.as_str())) {
+                Ok(claims) => claims,
+                Err(error) => {
+                    println!("{}: {}", path, error);
+                    return;
+                }
+            };
+            let options = if args[0] == "convert" { 3 } else { 2 };
+            let (sheet, policy) = sheet_options(args.get(options..).unwrap_or_default());
+            let claims = on_sheet(&claims, sheet, policy);
 
-    for claim in claims.iter() {
-        claim_cloth_area(claim, &mut cloth);
-    }
+            if args[0] == "convert" {
+                let format = args
+                    .get(2)
+                    .map_or(Format::Claims, |f| Format::from(f.as_str()));
+                print!("{}", formats::write(&claims, format));
+                return;
+            }
 
-    for claim in claims.iter() {
-        if !has_overlaps(claim, &cloth) {
-            return claim.id;
+            let mut cloth = Fabric::for_claims(&claims);
+            for claim in claims.iter() {
+                cloth.claim(claim);
+            }
+            for id in cloth.intact_ids() {
+                println!("{}", id);
+            }
         }
-    }
+        Some("sweep") => {
+            let claims = load_claims(input);
+            for id in sweep::intact_claim_ids(&claims) {
+                println!("{}", id);
+            }
+        }
+        Some("render") => {
+            let claims = load_claims(input);
+            let mut cloth = Fabric::for_claims(&claims);
+            for claim in claims.iter() {
+                cloth.claim(claim);
+            }
 
-    0
-}
+            match args.get(1).map(String::as_str) {
+                Some("ascii") => print!("{}", render::render_ascii(&claims, &cloth)),
+                _ => {
+                    let path = args.get(2).map_or("fabric.ppm", String::as_str);
+                    let intact_ids = sweep::intact_claim_ids(&claims);
+                    let image = render::render_ppm(&claims, &cloth, &intact_ids);
+                    fs::write(path, image).expect("couldn't write image");
+                }
+            }
+        }
+        Some("at") | Some("within") => {
+            let claims = load_claims(input);
+            let index = ClaimIndex::new(&claims);
+            let numbers: Option<Vec<u32>> = args[1..].iter().map(|n| n.parse().ok()).collect();
+
+            let ids = match (args[0].as_str(), numbers.as_deref()) {
+                ("at", Some(&[x, y])) => index.covering(x, y),
+                ("within", Some(&[x, y, width, height])) => index.intersecting(x, y, width, height),
+                _ => {
+                    println!("usage: at <x> <y> | within <x> <y> <width> <height>");
+                    return;
+                }
+            };
+            for id in ids {
+                println!("{}", id);
+            }
+        }
+        Some("place") | Some("free") => {
+            let claims = load_claims(input);
+            let mut cloth = Fabric::for_claims(&claims);
+            for claim in claims.iter() {
+                cloth.claim(claim);
+            }
+
+            if args[0] == "free" {
+                let (sheet, _policy) = sheet_options(&args[1..]);
+                match placement::largest_free_region(&cloth, sheet) {
+                    Some(region) => println!(
+                        "{},{}: {}x{}",
+                        region.x, region.y, region.width, region.height
+                    ),
+                    None => println!("no unclaimed fabric"),
+                }
+                return;
+            }
+
+            let size: Vec<u32> = args[1..]
+                .iter()
+                .take(2)
+                .filter_map(|n| n.parse().ok())
+                .collect();
+            let (width, height) = match *size.as_slice() {
+                [width, height] => (width, height),
+                _ => {
+                    println!("usage: place <width> <height> [<sheet width> <sheet height>]");
+                    return;
+                }
+            };
+            let (sheet, _policy) = sheet_options(&args[3..]);
+            match placement::find_placement(&cloth, sheet, width, height) {
+                Some(place) => println!("{},{} overlapping {}", place.x, place.y, place.overlap),
+                None => println!("a {}x{} claim doesn't fit on the fabric", width, height),
+            }
+        }
+        Some("edit") => {
+            let claims = load_claims(input);
+            let mut cloth = Fabric::for_claims(&claims);
+            for claim in claims.iter() {
+                cloth.claim(claim);
+            }
+
+            for (i, line) in io::stdin().lock().lines().enumerate() {
+                let line = line.expect("couldn't read from stdin");
+                let line = line.trim();
+
+                if let Some(claim) = line.strip_prefix('+') {
+                    let claim = match formats::parse_claim(claim) {
+                        Ok(claim) => claim,
+                        Err(error) => {
+                            println!("{}", error);
+                            println!("usage: +#<id> @ <x>,<y>: <width>x<height> | -<id>");
+                            continue;
+                        }
+                    };
+                    if let Err(error) = Sheet::default().check(i + 1, &claim) {
+                        println!("{}", error);
+                        continue;
+                    }
+                    if cloth.slot_of(claim.id).is_some() {
+                        println!("claim #{} already exists", claim.id);
+                        continue;
+                    }
+                    cloth.claim(&claim);
+                } else if let Some(id) = line.strip_prefix('-') {
+                    let slot = id.trim().parse().ok().and_then(|id| cloth.slot_of(id));
+                    match slot {
+                        Some(slot) => {
+                            cloth.unclaim(slot);
+                        }
+                        None => println!("no claim {}", id.trim()),
+                    }
+                } else {
+                    println!("usage: +#<id> @ <x>,<y>: <width>x<height> | -<id>");
+                    continue;
+                }
 
-fn claim_cloth_area(claim: &Claim, cloth: &mut HashMap<(u32, u32), u32>) {
-    let x_min = claim.x;
-    let x_max = claim.x + claim.width;
-    let y_min = claim.y;
-    let y_max = claim.y + claim.height;
+                let intact: Vec<String> = cloth.intact_ids().iter().map(u32::to_string).collect();
+                println!(
+                    "overlapping {} intact [{}]",
+                    cloth.overlapping_sq_inches(),
+                    intact.join(", ")
+                );
+            }
+        }
+        Some("check") => {
+            let claims = match formats::from_claims(input) {
+                Ok(claims) => claims,
+                Err(error) => {
+                    println!("{}", error);
+                    return;
+                }
+            };
+            let (sheet, policy) = sheet_options(&args[1..]);
+            let (kept, errors) = sheet.validate(&claims, policy);
+            for error in errors.iter() {
+                println!("{}", error);
+            }
+            println!("{} of {} claims kept", kept.len(), claims.len());
+        }
+        Some("boxes") => {
+            let text = match args.get(1) {
+                Some(path) => fs::read_to_string(path).expect("couldn't read claims"),
+                None => String::from(input),
+            };
+            let boxes = match boxes::read(&text) {
+                Ok(boxes) => boxes,
+                Err(error) => {
+                    println!("{}", error);
+                    return;
+                }
+            };
 
-    for x in x_min..x_max {
-        for y in y_min..y_max {
-            *cloth.entry((x, y)).or_insert(0) += 1;
+            match boxes::overlapping_volume(&boxes) {
+                Ok(volume) => println!("{}", volume),
+                Err(error) => {
+                    println!("{}", error);
+                    return;
+                }
+            }
+            for id in boxes::intact_ids(&boxes) {
+                println!("{}", id);
+            }
+        }
+        Some("pairs") => {
+            let claims = load_claims(input);
+            for (a, b, area) in OverlapGraph::new(&claims).pairs() {
+                println!("#{} #{} {}", a, b, area);
+            }
+        }
+        Some("conflicts") => {
+            let claims = load_claims(input);
+            let graph = OverlapGraph::new(&claims);
+            let id = match args
+                .get(1)
+                .and_then(|id| id.strip_prefix('#').unwrap_or(id).parse().ok())
+            {
+                Some(id) => id,
+                None => {
+                    println!("usage: conflicts <id>");
+                    return;
+                }
+            };
+
+            match (graph.conflicts(id), graph.component_of(id)) {
+                (Some(conflicts), Some(component)) => {
+                    for conflict in conflicts {
+                        println!("#{} overlaps {} sq inches", conflict.id, conflict.area);
+                    }
+                    println!("#{} is in a group of {} claims", id, component.len());
+                }
+                _ => println!("claim #{} not found", id),
+            }
+        }
+        _ => {
+            let ids = find_non_overlapping_claim_ids(input);
+            if ids.is_empty() {
+                println!("no intact claims");
+            }
+            for id in ids {
+                println!("{}", id);
+            }
         }
     }
 }
 
-fn has_overlaps(claim: &Claim, cloth: &HashMap<(u32, u32), u32>) -> bool {
-    let x_min = claim.x;
-    let x_max = claim.x + claim.width;
-    let y_min = claim.y;
-    let y_max = claim.y + claim.height;
+// The solvers read the puzzle input through here. Lines that don't parse, and
+// claims whose far edge overflows u32 or leaves the sheet, are reported and
+// skipped so they never reach the cloth. `edit` checks each added claim as it
+// arrives, and `check` validates on its own so it can report every claim.
+fn load_claims(input: &str) -> Vec<Claim> {
+    let sheet = Sheet::default();
+    let mut claims = Vec::new();
 
-    for x in x_min..x_max {
-        for y in y_min..y_max {
-            let overlaps = cloth.get(&(x, y)).unwrap_or(&0);
-            if overlaps > &1 {
-                return true;
-            }
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
         }
+
+        let claim = formats::parse_claim(line)
+            .map_err(|message| {
+                FormatError {
+                    line: i + 1,
+                    message,
+                }
+                .to_string()
+            })
+            .and_then(|claim| match sheet.check(i + 1, &claim) {
+                Ok(()) => Ok(claim),
+                Err(error) => Err(error.to_string()),
+            });
+        match claim {
+            Ok(claim) => claims.push(claim),
+            Err(error) => eprintln!("warning: {}", error),
+        }
+    }
+
+    claims
+}
+
+fn on_sheet(claims: &[Claim], sheet: Sheet, policy: Policy) -> Vec<Claim> {
+    let (kept, errors) = sheet.validate(claims, policy);
+    for error in errors {
+        eprintln!("warning: {}", error);
+    }
+    kept
+}
+
+// Reads `[clip] [<sheet width> <sheet height>]`, the sheet options shared by
+// `check`, `import`, `convert`, `place` and `free`.
+fn sheet_options(args: &[String]) -> (Sheet, Policy) {
+    let policy = match args.first().map(String::as_str) {
+        Some("clip") => Policy::Clip,
+        _ => Policy::Reject,
+    };
+    let numbers: Vec<u32> = args.iter().filter_map(|n| n.parse().ok()).collect();
+    let sheet = match *numbers.as_slice() {
+        [width, height] => Sheet::new(width, height),
+        _ => Sheet::default(),
+    };
+
+    (sheet, policy)
+}
+
+fn find_non_overlapping_claim_ids(input: &str) -> Vec<u32> {
+    let claims = load_claims(input);
+    let mut cloth = Fabric::for_claims(&claims);
+
+    for claim in claims.iter() {
+        cloth.claim(claim);
     }
 
-    false
+    claims
+        .iter()
+        .filter(|claim| !cloth.has_overlaps(claim))
+        .map(|claim| claim.id)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    pub fn claims(input: &str) -> Vec<Claim> {
+        input.lines().map(Claim::from).collect()
+    }
+
+    pub fn claimed(input: &str) -> (Vec<Claim>, Fabric) {
+        let claims = claims(input);
+        let mut cloth = Fabric::for_claims(&claims);
+        for claim in claims.iter() {
+            cloth.claim(claim);
+        }
+        (claims, cloth)
+    }
+
     #[test]
     fn parse_claim() {
         let claim_str = "#123 @ 3,2: 5x4";
@@ -100,4 +412,34 @@ mod tests {
         assert_eq!(claim.width, 5);
         assert_eq!(claim.height, 4);
     }
+
+    #[test]
+    fn no_intact_claims() {
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4";
+        assert_eq!(find_non_overlapping_claim_ids(input), vec![]);
+    }
+
+    #[test]
+    fn one_intact_claim() {
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2";
+        assert_eq!(find_non_overlapping_claim_ids(input), vec![3]);
+    }
+
+    #[test]
+    fn several_intact_claims() {
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#0 @ 0,0: 1x1\n#4 @ 8,8: 1x1";
+        assert_eq!(find_non_overlapping_claim_ids(input), vec![3, 0, 4]);
+    }
+
+    #[test]
+    fn overflowing_claims_never_reach_the_cloth() {
+        let input = "#1 @ 4294967295,0: 2x1\n#2 @ 0,0: 1x1\n#3 @ 999,999: 2x2";
+        assert_eq!(find_non_overlapping_claim_ids(input), vec![2]);
+    }
+
+    #[test]
+    fn unparsable_claims_are_not_read_as_zero() {
+        let input = "#x @ 7,7: 1x1\n#1 @ 0,0: 1x1\n#0 @ 3,3: 1x1";
+        assert_eq!(find_non_overlapping_claim_ids(input), vec![1, 0]);
+    }
 }
@@ -0,0 +1,177 @@
+use crate::fabric::Fabric;
+use crate::sheet::Sheet;
+
+#[derive(Debug, PartialEq)]
+pub struct Placement {
+    pub x: u32,
+    pub y: u32,
+    pub overlap: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Finds the top-left corner for a new `width` x `height` claim that covers
+// the fewest already-claimed inches, preferring the topmost, then leftmost,
+// position on ties. Returns None if the claim doesn't fit on the sheet.
+pub fn find_placement(cloth: &Fabric, sheet: Sheet, width: u32, height: u32) -> Option<Placement> {
+    let (sheet_width, sheet_height) = (sheet.width, sheet.height);
+    if width > sheet_width || height > sheet_height {
+        return None;
+    }
+
+    let stride = sheet_width as usize + 1;
+    let mut claimed = vec![0u64; stride * (sheet_height as usize + 1)];
+
+    for y in 0..sheet_height as usize {
+        for x in 0..sheet_width as usize {
+            let inch = if cloth.count(x as u32, y as u32) > 0 {
+                1
+            } else {
+                0
+            };
+            claimed[(y + 1) * stride + x + 1] =
+                inch + claimed[y * stride + x + 1] + claimed[(y + 1) * stride + x]
+                    - claimed[y * stride + x];
+        }
+    }
+
+    let (w, h) = (width as usize, height as usize);
+    let mut best: Option<Placement> = None;
+
+    for y in 0..=(sheet_height - height) as usize {
+        for x in 0..=(sheet_width - width) as usize {
+            let overlap = claimed[(y + h) * stride + x + w] + claimed[y * stride + x]
+                - claimed[y * stride + x + w]
+                - claimed[(y + h) * stride + x];
+
+            if best.as_ref().is_none_or(|best| overlap < best.overlap) {
+                best = Some(Placement {
+                    x: x as u32,
+                    y: y as u32,
+                    overlap,
+                });
+                if overlap == 0 {
+                    return best;
+                }
+            }
+        }
+    }
+
+    best
+}
+
+// Treats each row as the floor of a histogram of unclaimed column heights
+// and keeps the largest rectangle found under any of them.
+pub fn largest_free_region(cloth: &Fabric, sheet: Sheet) -> Option<Region> {
+    let (sheet_width, sheet_height) = (sheet.width, sheet.height);
+    let mut heights = vec![0u32; sheet_width as usize];
+    let mut best: Option<Region> = None;
+
+    for y in 0..sheet_height {
+        for (x, height) in heights.iter_mut().enumerate() {
+            *height = if cloth.count(x as u32, y) == 0 {
+                *height + 1
+            } else {
+                0
+            };
+        }
+
+        let mut stack: Vec<usize> = Vec::new();
+        for x in 0..=heights.len() {
+            let current = heights.get(x).copied().unwrap_or(0);
+
+            while let Some(&top) = stack.last() {
+                if heights[top] <= current {
+                    break;
+                }
+                stack.pop();
+
+                let height = heights[top];
+                let left = stack.last().map_or(0, |&i| i + 1);
+                let width = (x - left) as u32;
+                let area = width as u64 * height as u64;
+
+                if best
+                    .as_ref()
+                    .is_none_or(|best| area > best.width as u64 * best.height as u64)
+                {
+                    best = Some(Region {
+                        x: left as u32,
+                        y: y + 1 - height,
+                        width,
+                        height,
+                    });
+                }
+            }
+
+            stack.push(x);
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claimed;
+
+    #[test]
+    fn places_in_free_space() {
+        let (_, cloth) = claimed("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2");
+
+        assert_eq!(
+            find_placement(&cloth, Sheet::new(8, 8), 3, 1),
+            Some(Placement {
+                x: 0,
+                y: 0,
+                overlap: 0
+            })
+        );
+        assert_eq!(
+            find_placement(&cloth, Sheet::new(8, 8), 3, 3),
+            Some(Placement {
+                x: 0,
+                y: 0,
+                overlap: 0
+            })
+        );
+        assert_eq!(find_placement(&cloth, Sheet::new(8, 8), 9, 1), None);
+    }
+
+    #[test]
+    fn minimises_overlap_when_full() {
+        let (_, cloth) = claimed("#1 @ 0,0: 4x4\n#2 @ 0,0: 3x4");
+        assert_eq!(
+            find_placement(&cloth, Sheet::new(5, 4), 2, 2),
+            Some(Placement {
+                x: 3,
+                y: 0,
+                overlap: 2
+            })
+        );
+    }
+
+    #[test]
+    fn largest_free_region() {
+        let (_, cloth) = claimed("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2");
+        assert_eq!(
+            super::largest_free_region(&cloth, Sheet::new(8, 8)),
+            Some(Region {
+                x: 0,
+                y: 0,
+                width: 3,
+                height: 3
+            })
+        );
+
+        let (_, full) = claimed("#1 @ 0,0: 2x2");
+        assert_eq!(super::largest_free_region(&full, Sheet::new(2, 2)), None);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::fabric::Fabric;
+use crate::Claim;
+
+const EMPTY: [u8; 3] = [24, 24, 24];
+const SINGLE: [u8; 3] = [60, 150, 60];
+const DOUBLE: [u8; 3] = [240, 200, 0];
+const MOST: [u8; 3] = [220, 0, 0];
+const OUTLINE: [u8; 3] = [0, 160, 255];
+
+// Matches the puzzle's diagram: `.` for unclaimed inches, the claim's id
+// (last digit) where exactly one claim covers an inch, and `X` for overlaps.
+pub fn render_ascii(claims: &[Claim], cloth: &Fabric) -> String {
+    let (width, height) = extent(claims);
+    let mut out = String::new();
+
+    for y in 0..height + 1 {
+        for x in 0..width + 1 {
+            let c = match cloth.count(x, y) {
+                0 => '.',
+                1 => claims
+                    .iter()
+                    .find(|claim| covers(claim, x, y))
+                    .and_then(|claim| std::char::from_digit(claim.id % 10, 10))
+                    .unwrap_or('#'),
+                _ => 'X',
+            };
+            out.push(c);
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+// Binary PPM (P6), one pixel per square inch. Inches claimed twice are
+// yellow, shading to red as they approach the most-claimed inch on the sheet.
+pub fn render_ppm(claims: &[Claim], cloth: &Fabric, intact_ids: &[u32]) -> Vec<u8> {
+    let (width, height) = extent(claims);
+    let max_count = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| cloth.count(x, y))
+        .max()
+        .unwrap_or(0);
+
+    let mut pixels: Vec<[u8; 3]> = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height {
+        for x in 0..width {
+            pixels.push(colour(cloth.count(x, y), max_count));
+        }
+    }
+
+    for claim in claims.iter().filter(|claim| intact_ids.contains(&claim.id)) {
+        for x in claim.x..claim.x + claim.width {
+            for y in claim.y..claim.y + claim.height {
+                let on_edge = x == claim.x
+                    || y == claim.y
+                    || x == claim.x + claim.width - 1
+                    || y == claim.y + claim.height - 1;
+                if on_edge {
+                    pixels[(y * width + x) as usize] = OUTLINE;
+                }
+            }
+        }
+    }
+
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    image.extend(pixels.iter().flatten());
+    image
+}
+
+fn colour(count: u32, max_count: u32) -> [u8; 3] {
+    match count {
+        0 => EMPTY,
+        1 => SINGLE,
+        2 => DOUBLE,
+        _ => {
+            let t = (count - 2) as f64 / (max_count - 2) as f64;
+            let mut rgb = [0; 3];
+            for i in 0..3 {
+                rgb[i] = (DOUBLE[i] as f64 + (MOST[i] as f64 - DOUBLE[i] as f64) * t) as u8;
+            }
+            rgb
+        }
+    }
+}
+
+fn extent(claims: &[Claim]) -> (u32, u32) {
+    let width = claims.iter().map(|c| c.x + c.width).max().unwrap_or(0);
+    let height = claims.iter().map(|c| c.y + c.height).max().unwrap_or(0);
+    (width, height)
+}
+
+fn covers(claim: &Claim, x: u32, y: u32) -> bool {
+    claim.x <= x && x < claim.x + claim.width && claim.y <= y && y < claim.y + claim.height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claimed;
+
+    #[test]
+    fn ascii_example() {
+        let (claims, cloth) = claimed("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2");
+        let expected = "\
+........
+...2222.
+...2222.
+.11XX22.
+.11XX22.
+.111133.
+.111133.
+........
+";
+        assert_eq!(render_ascii(&claims, &cloth), expected);
+    }
+
+    #[test]
+    fn ppm_example() {
+        let (claims, cloth) = claimed("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#4 @ 3,3: 1x1");
+        let image = render_ppm(&claims, &cloth, &[3]);
+
+        let header = b"P6\n7 7\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 7 * 7 * 3);
+
+        let pixel = |x: usize, y: usize| {
+            let start = header.len() + (y * 7 + x) * 3;
+            [image[start], image[start + 1], image[start + 2]]
+        };
+        assert_eq!(pixel(0, 0), EMPTY);
+        assert_eq!(pixel(1, 3), SINGLE);
+        assert_eq!(pixel(4, 3), DOUBLE);
+        assert_eq!(pixel(3, 3), MOST);
+        assert_eq!(pixel(5, 5), OUTLINE);
+        assert_eq!(pixel(6, 6), OUTLINE);
+    }
+}
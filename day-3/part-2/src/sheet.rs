@@ -0,0 +1,177 @@
+use crate::Claim;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sheet {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for Sheet {
+    fn default() -> Self {
+        Sheet {
+            width: 1000,
+            height: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Policy {
+    Reject,
+    Clip,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BoundsError {
+    Overflow { line: usize, id: u32 },
+    PastSheet { line: usize, id: u32, clipped: bool },
+    OffSheet { line: usize, id: u32 },
+}
+
+impl fmt::Display for BoundsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoundsError::Overflow { line, id } => {
+                write!(f, "line {}: claim #{} overflows u32 coordinates", line, id)
+            }
+            BoundsError::PastSheet { line, id, clipped } => {
+                let action = if *clipped { "clipped" } else { "rejected" };
+                write!(
+                    f,
+                    "line {}: claim #{} extends past the sheet ({})",
+                    line, id, action
+                )
+            }
+            BoundsError::OffSheet { line, id } => {
+                write!(
+                    f,
+                    "line {}: claim #{} lies entirely off the sheet",
+                    line, id
+                )
+            }
+        }
+    }
+}
+
+impl Error for BoundsError {}
+
+impl Sheet {
+    pub fn new(width: u32, height: u32) -> Self {
+        Sheet { width, height }
+    }
+
+    // Checks that the claim's far edges fit in u32 and on the sheet. `line`
+    // is only used to label the error.
+    pub fn check(&self, line: usize, claim: &Claim) -> Result<(), BoundsError> {
+        let id = claim.id;
+        let x_max = claim.x as u64 + claim.width as u64;
+        let y_max = claim.y as u64 + claim.height as u64;
+
+        if x_max > u32::MAX as u64 || y_max > u32::MAX as u64 {
+            return Err(BoundsError::Overflow { line, id });
+        }
+        if claim.x >= self.width || claim.y >= self.height {
+            return Err(BoundsError::OffSheet { line, id });
+        }
+        if x_max > self.width as u64 || y_max > self.height as u64 {
+            return Err(BoundsError::PastSheet {
+                line,
+                id,
+                clipped: false,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn clip(&self, claim: &Claim) -> Option<Claim> {
+        if claim.x >= self.width || claim.y >= self.height {
+            return None;
+        }
+
+        Some(Claim {
+            width: claim.width.min(self.width - claim.x),
+            height: claim.height.min(self.height - claim.y),
+            ..claim.clone()
+        })
+    }
+
+    // Every problem is reported whichever policy is used. With `Clip`, claims
+    // that overflow or extend past the sheet are trimmed to fit and kept;
+    // only those lying entirely off the sheet are dropped.
+    pub fn validate(&self, claims: &[Claim], policy: Policy) -> (Vec<Claim>, Vec<BoundsError>) {
+        let mut kept = Vec::new();
+        let mut errors = Vec::new();
+
+        for (i, claim) in claims.iter().enumerate() {
+            match (self.check(i + 1, claim), policy) {
+                (Ok(()), _) => kept.push(claim.clone()),
+                (Err(BoundsError::PastSheet { line, id, .. }), Policy::Clip) => {
+                    kept.extend(self.clip(claim));
+                    errors.push(BoundsError::PastSheet {
+                        line,
+                        id,
+                        clipped: true,
+                    });
+                }
+                (Err(BoundsError::Overflow { line, id }), Policy::Clip) => {
+                    kept.extend(self.clip(claim));
+                    errors.push(BoundsError::Overflow { line, id });
+                }
+                (Err(error), _) => errors.push(error),
+            }
+        }
+
+        (kept, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claims;
+
+    #[test]
+    fn reject_out_of_bounds() {
+        let claims = claims("#1 @ 1,1: 2x2\n#2 @ 3,3: 2x2\n#3 @ 4,0: 1x1\n#4 @ 4294967295,0: 2x1");
+        let (kept, errors) = Sheet::new(4, 4).validate(&claims, Policy::Reject);
+
+        assert_eq!(kept.iter().map(|c| c.id).collect::<Vec<u32>>(), vec![1]);
+        assert_eq!(
+            errors,
+            vec![
+                BoundsError::PastSheet {
+                    line: 2,
+                    id: 2,
+                    clipped: false
+                },
+                BoundsError::OffSheet { line: 3, id: 3 },
+                BoundsError::Overflow { line: 4, id: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn clip_out_of_bounds() {
+        let claims = claims("#1 @ 1,1: 2x2\n#2 @ 3,3: 2x2\n#3 @ 4,0: 1x1\n#4 @ 2,4294967295: 2x1");
+        let (kept, errors) = Sheet::new(4, 4).validate(&claims, Policy::Clip);
+
+        let kept: Vec<(u32, u32, u32)> = kept.iter().map(|c| (c.id, c.width, c.height)).collect();
+        assert_eq!(kept, vec![(1, 2, 2), (2, 1, 1)]);
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2: claim #2 extends past the sheet (clipped)"
+        );
+    }
+
+    #[test]
+    fn default_sheet_fits_input() {
+        let claims = claims(include_str!("./input.txt"));
+        let (kept, errors) = Sheet::default().validate(&claims, Policy::Reject);
+        assert_eq!(kept.len(), claims.len());
+        assert!(errors.is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::Claim;
+
+// Sweeps across the claims from left to right, keeping only those whose
+// right edge is still past the sweep line. A claim can only overlap one of
+// those, so each claim is compared against its active neighbours rather than
+// every square inch it covers. Edges are widened to u64 so claims reaching
+// u32::MAX don't overflow.
+pub fn overlapping_pairs(claims: &[Claim]) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..claims.len())
+        .filter(|&i| claims[i].width > 0 && claims[i].height > 0)
+        .collect();
+    order.sort_by_key(|&i| claims[i].x);
+
+    let mut pairs = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+
+    for &i in order.iter() {
+        let claim = &claims[i];
+        active.retain(|&j| right(&claims[j]) > claim.x as u64);
+
+        for &j in active.iter() {
+            if spans_overlap(claim.y, claim.height, claims[j].y, claims[j].height) {
+                pairs.push((j.min(i), j.max(i)));
+            }
+        }
+
+        active.push(i);
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+pub fn intact_claim_ids(claims: &[Claim]) -> Vec<u32> {
+    let mut overlapped = vec![false; claims.len()];
+    for (a, b) in overlapping_pairs(claims) {
+        overlapped[a] = true;
+        overlapped[b] = true;
+    }
+
+    claims
+        .iter()
+        .zip(overlapped)
+        .filter(|(_claim, overlapped)| !overlapped)
+        .map(|(claim, _overlapped)| claim.id)
+        .collect()
+}
+
+fn right(claim: &Claim) -> u64 {
+    claim.x as u64 + claim.width as u64
+}
+
+fn spans_overlap(start_a: u32, len_a: u32, start_b: u32, len_b: u32) -> bool {
+    (start_a as u64) < start_b as u64 + len_b as u64
+        && (start_b as u64) < start_a as u64 + len_a as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::claims;
+
+    #[test]
+    fn example() {
+        let claims = claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2");
+        assert_eq!(intact_claim_ids(&claims), vec![3]);
+    }
+
+    #[test]
+    fn pairs_are_ordered_by_index() {
+        let claims = claims("#1 @ 5,0: 4x4\n#2 @ 0,0: 6x2\n#3 @ 7,3: 2x2");
+        assert_eq!(overlapping_pairs(&claims), vec![(0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn touching_edges_do_not_overlap() {
+        let claims = claims("#1 @ 0,0: 2x2\n#2 @ 2,0: 2x2\n#3 @ 0,2: 2x2");
+        assert_eq!(intact_claim_ids(&claims), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn coordinates_near_u32_max() {
+        let claims = claims(
+            "#1 @ 4294967290,4294967290: 10x10\n#2 @ 4294967295,4294967295: 5x5\n#3 @ 0,0: 1x1",
+        );
+        assert_eq!(intact_claim_ids(&claims), vec![3]);
+    }
+
+    #[test]
+    fn agrees_with_cloth_map() {
+        let input = include_str!("./input.txt");
+        let claims = claims(input);
+        assert_eq!(
+            intact_claim_ids(&claims),
+            crate::find_non_overlapping_claim_ids(input)
+        );
+    }
+}
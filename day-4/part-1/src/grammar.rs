@@ -0,0 +1,178 @@
+use crate::GuardEvent;
+use crate::Record;
+use chrono::NaiveDateTime;
+use std::error::Error;
+use std::fmt;
+
+const GUARD_ID: &str = "{id}";
+
+#[derive(Debug, PartialEq)]
+pub struct GrammarError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for GrammarError {}
+
+// How one facility writes its guard log: a chrono format for the timestamp
+// that starts every line, and the phrases for each event. The shift phrase
+// marks where the guard's number sits with `{id}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grammar {
+    pub timestamp: String,
+    pub begin_shift: String,
+    pub fall_asleep: String,
+    pub wake_up: String,
+}
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Grammar {
+            timestamp: String::from("[%Y-%m-%d %H:%M]"),
+            begin_shift: String::from("Guard #{id} begins shift"),
+            fall_asleep: String::from("falls asleep"),
+            wake_up: String::from("wakes up"),
+        }
+    }
+}
+
+impl Grammar {
+    // One `key = value` per line; any key left out keeps the puzzle's phrasing.
+    pub fn read(text: &str) -> Result<Self, GrammarError> {
+        let mut grammar = Grammar::default();
+
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let error = |message: String| GrammarError {
+                line: i + 1,
+                message,
+            };
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(String::from("expected `key = value`")))?;
+            let value = value.trim().to_string();
+
+            match key.trim() {
+                "timestamp" => grammar.timestamp = value,
+                "begin_shift" if !value.contains(GUARD_ID) => {
+                    return Err(error(format!("`begin_shift` must contain `{}`", GUARD_ID)));
+                }
+                "begin_shift" => grammar.begin_shift = value,
+                "fall_asleep" => grammar.fall_asleep = value,
+                "wake_up" => grammar.wake_up = value,
+                key => return Err(error(format!("unknown key `{}`", key))),
+            }
+        }
+
+        Ok(grammar)
+    }
+
+    pub fn parse(&self, line: &str) -> Result<Record, String> {
+        let (timestamp, rest) = NaiveDateTime::parse_and_remainder(line, &self.timestamp)
+            .map_err(|e| format!("couldn't parse timestamp: {}", e))?;
+
+        let event = match rest.trim() {
+            s if s == self.wake_up => GuardEvent::WakeUp,
+            s if s == self.fall_asleep => GuardEvent::FallAsleep,
+            s => GuardEvent::BeginShift(self.guard_id(s)?),
+        };
+
+        Ok(Record {
+            line: 0,
+            timestamp: timestamp.and_utc(),
+            event,
+        })
+    }
+
+    fn guard_id(&self, event: &str) -> Result<u32, String> {
+        let (prefix, suffix) = self
+            .begin_shift
+            .split_once(GUARD_ID)
+            .expect("begin_shift always contains the guard id");
+
+        event
+            .strip_prefix(prefix)
+            .and_then(|s| s.strip_suffix(suffix))
+            .ok_or_else(|| format!("unrecognised event `{}`", event))?
+            .parse()
+            .map_err(|_| format!("couldn't parse guard number in `{}`", event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    #[test]
+    fn puzzle_grammar() {
+        let grammar = Grammar::default();
+
+        let record = grammar
+            .parse("[1518-11-01 23:58] Guard #99 begins shift")
+            .unwrap();
+        assert_eq!(
+            record.timestamp,
+            Utc.with_ymd_and_hms(1518, 11, 1, 23, 58, 0).unwrap()
+        );
+        assert!(matches!(record.event, GuardEvent::BeginShift(99)));
+        assert!(matches!(
+            grammar
+                .parse("[1518-11-02 00:40] falls asleep")
+                .unwrap()
+                .event,
+            GuardEvent::FallAsleep
+        ));
+
+        assert!(grammar.parse("1518-11-02 00:40 falls asleep").is_err());
+        assert!(grammar.parse("[1518-11-02 00:40] dozes off").is_err());
+        assert!(grammar
+            .parse("[1518-11-02 00:40] Guard #x begins shift")
+            .is_err());
+    }
+
+    #[test]
+    fn configured_grammar() {
+        let grammar = Grammar::read(
+            "timestamp = %d/%m/%Y %H:%M -\nbegin_shift = guard {id} on duty\nfall_asleep = dozes off\n",
+        )
+        .unwrap();
+        assert_eq!(grammar.wake_up, "wakes up");
+
+        let record = grammar
+            .parse("02/11/1518 00:03 - guard 42 on duty")
+            .unwrap();
+        assert_eq!(
+            record.timestamp,
+            Utc.with_ymd_and_hms(1518, 11, 2, 0, 3, 0).unwrap()
+        );
+        assert!(matches!(record.event, GuardEvent::BeginShift(42)));
+        assert!(matches!(
+            grammar.parse("02/11/1518 00:10 - dozes off").unwrap().event,
+            GuardEvent::FallAsleep
+        ));
+    }
+
+    #[test]
+    fn bad_grammar() {
+        assert_eq!(
+            Grammar::read("wake_up = wakes\nbegin_shift = guard on duty"),
+            Err(GrammarError {
+                line: 2,
+                message: String::from("`begin_shift` must contain `{id}`"),
+            })
+        );
+        assert_eq!(Grammar::read("colour = red").unwrap_err().line, 1);
+        assert_eq!(Grammar::read("\nnonsense").unwrap_err().line, 2);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::shift::Shift;
+use crate::sleep::SleepModel;
+
+const EMPTY: [u8; 3] = [24, 24, 24];
+const COOL: [u8; 3] = [40, 60, 160];
+const HOT: [u8; 3] = [240, 40, 0];
+const ASLEEP: [u8; 3] = [240, 200, 0];
+const SHADES: &[u8] = b" .:-=+*#%@";
+
+// Pixels per minute (and per row) in the rendered images.
+const CELL: usize = 8;
+
+// One row per guard, one column per minute, shaded by how many times the
+// guard slept through that minute relative to the busiest cell on the map.
+pub fn render_terminal(model: &SleepModel) -> String {
+    let max_count = max_count(model);
+    let id_width = model
+        .guards
+        .keys()
+        .map(|guard_id| format!("#{}", guard_id).len())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    for (guard_id, guard) in model.guards.iter() {
+        let row: String = guard
+            .histogram
+            .iter()
+            .map(|&times| shade(times, max_count))
+            .collect();
+        out.push_str(&format!(
+            "{:>width$} {}\n",
+            format!("#{}", guard_id),
+            row,
+            width = id_width
+        ));
+    }
+    out
+}
+
+// Binary PPM (P6) of the same map, CELL pixels square per guard and minute.
+pub fn render_ppm(model: &SleepModel) -> Vec<u8> {
+    let max_count = max_count(model);
+    let rows: Vec<Vec<[u8; 3]>> = model
+        .guards
+        .values()
+        .map(|guard| {
+            guard
+                .histogram
+                .iter()
+                .map(|&times| colour(times, max_count))
+                .collect()
+        })
+        .collect();
+
+    ppm(&rows, model.bucket.len())
+}
+
+// One strip per shift in date order, lit wherever the guard was asleep
+// during the midnight hour.
+pub fn render_strips(shifts: &[Shift]) -> Vec<u8> {
+    let rows: Vec<Vec<[u8; 3]>> = shifts
+        .iter()
+        .map(|shift| {
+            (0..60)
+                .map(|minute| {
+                    if shift.is_asleep(minute) {
+                        ASLEEP
+                    } else {
+                        EMPTY
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    ppm(&rows, 60)
+}
+
+fn ppm(rows: &[Vec<[u8; 3]>], columns: usize) -> Vec<u8> {
+    let mut image = format!("P6\n{} {}\n255\n", columns * CELL, rows.len() * CELL).into_bytes();
+    for row in rows {
+        for _ in 0..CELL {
+            for pixel in row {
+                for _ in 0..CELL {
+                    image.extend(pixel);
+                }
+            }
+        }
+    }
+    image
+}
+
+fn max_count(model: &SleepModel) -> u32 {
+    model
+        .guards
+        .values()
+        .flat_map(|guard| guard.histogram.iter().copied())
+        .max()
+        .unwrap_or(0)
+}
+
+fn shade(times: u32, max_count: u32) -> char {
+    if times == 0 {
+        return SHADES[0] as char;
+    }
+    let steps = SHADES.len() - 1;
+    let index = 1 + (times - 1) as usize * (steps - 1) / (max_count.max(2) - 1) as usize;
+    SHADES[index] as char
+}
+
+fn colour(times: u32, max_count: u32) -> [u8; 3] {
+    if times == 0 {
+        return EMPTY;
+    }
+    let t = (times - 1) as f64 / (max_count.max(2) - 1) as f64;
+    let mut rgb = [0; 3];
+    for i in 0..3 {
+        rgb[i] = (COOL[i] as f64 + (HOT[i] as f64 - COOL[i] as f64) * t) as u8;
+    }
+    rgb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_naps;
+    use crate::get_records;
+    use crate::shift::get_shifts;
+    use crate::tests::EXAMPLE;
+    use crate::Bucket;
+
+    fn model() -> SleepModel {
+        SleepModel::new(&get_naps(&get_records(EXAMPLE)), Bucket::MinuteOfHour)
+    }
+
+    #[test]
+    fn terminal_heatmap() {
+        let lines: Vec<String> = render_terminal(&model())
+            .lines()
+            .map(String::from)
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(&lines[0][..4], "#10 ");
+        assert_eq!(&lines[0][4 + 4..4 + 6], " .");
+        assert_eq!(&lines[0][4 + 24..4 + 26], "+.");
+        assert_eq!(&lines[1][..4], "#99 ");
+        assert_eq!(&lines[1][4 + 35..4 + 37], " .");
+        assert_eq!(&lines[1][4 + 45..4 + 46], "@");
+    }
+
+    #[test]
+    fn ppm_heatmap() {
+        let image = render_ppm(&model());
+
+        let header = format!("P6\n{} {}\n255\n", 60 * CELL, 2 * CELL).into_bytes();
+        assert_eq!(&image[..header.len()], &header[..]);
+        assert_eq!(image.len(), header.len() + 60 * CELL * 2 * CELL * 3);
+
+        let pixel = |minute: usize, row: usize| {
+            let start = header.len() + (row * CELL * 60 * CELL + minute * CELL) * 3;
+            [image[start], image[start + 1], image[start + 2]]
+        };
+        assert_eq!(pixel(0, 0), EMPTY);
+        assert_eq!(pixel(5, 0), COOL);
+        assert_eq!(pixel(24, 0), colour(2, 3));
+        assert_eq!(pixel(36, 1), COOL);
+        assert_eq!(pixel(45, 1), HOT);
+    }
+
+    #[test]
+    fn date_strips() {
+        let shifts = get_shifts(&get_records(EXAMPLE));
+        let image = render_strips(&shifts);
+
+        let header = format!("P6\n{} {}\n255\n", 60 * CELL, 5 * CELL).into_bytes();
+        assert_eq!(&image[..header.len()], &header[..]);
+
+        let pixel = |minute: usize, row: usize| {
+            let start = header.len() + (row * CELL * 60 * CELL + minute * CELL) * 3;
+            [image[start], image[start + 1], image[start + 2]]
+        };
+        assert_eq!(pixel(5, 0), ASLEEP);
+        assert_eq!(pixel(25, 0), EMPTY);
+        assert_eq!(pixel(40, 1), ASLEEP);
+        assert_eq!(pixel(24, 2), ASLEEP);
+        assert_eq!(pixel(45, 4), ASLEEP);
+        assert_eq!(pixel(55, 4), EMPTY);
+    }
+}
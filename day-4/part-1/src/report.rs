@@ -0,0 +1,279 @@
+use crate::get_naps;
+use crate::sleep::SleepModel;
+use crate::Bucket;
+use crate::GuardEvent;
+use crate::GuardId;
+use crate::Minute;
+use crate::Record;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq)]
+pub struct GuardStats {
+    pub guard_id: GuardId,
+    pub shifts: u32,
+    pub total_asleep: u32,
+    pub longest_nap: u32,
+    pub most_slept_minute: Option<(Minute, u32)>,
+    pub histogram: Vec<u32>,
+}
+
+impl GuardStats {
+    pub fn mean_asleep(&self) -> f64 {
+        if self.shifts == 0 {
+            return 0.0;
+        }
+        self.total_asleep as f64 / self.shifts as f64
+    }
+
+    // The chance that, on any given shift, this guard was asleep at `minute`.
+    pub fn probability(&self, minute: Minute) -> f64 {
+        if self.shifts == 0 {
+            return 0.0;
+        }
+        self.histogram[minute as usize] as f64 / self.shifts as f64
+    }
+
+    pub fn probabilities(&self) -> Vec<f64> {
+        (0..self.histogram.len() as Minute)
+            .map(|minute| self.probability(minute))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Id,
+    Shifts,
+    TotalAsleep,
+    MeanAsleep,
+    LongestNap,
+    MinuteCount,
+}
+
+impl From<&str> for SortKey {
+    fn from(string: &str) -> Self {
+        match string {
+            "shifts" => SortKey::Shifts,
+            "total" => SortKey::TotalAsleep,
+            "mean" => SortKey::MeanAsleep,
+            "longest" => SortKey::LongestNap,
+            "minute" => SortKey::MinuteCount,
+            _ => SortKey::Id,
+        }
+    }
+}
+
+// Every guard who worked a shift gets a row, including those who never slept.
+pub fn guard_report(records: &[Record], bucket: Bucket) -> Vec<GuardStats> {
+    let sleep = SleepModel::new(&get_naps(records), bucket);
+
+    let mut shifts: BTreeMap<GuardId, u32> = BTreeMap::new();
+    for record in records {
+        if let GuardEvent::BeginShift(guard_id) = record.event {
+            *shifts.entry(guard_id).or_insert(0) += 1;
+        }
+    }
+
+    shifts
+        .into_iter()
+        .map(|(guard_id, shifts)| match sleep.guards.get(&guard_id) {
+            Some(guard) => GuardStats {
+                guard_id,
+                shifts,
+                total_asleep: guard.total_asleep(),
+                longest_nap: guard.longest_nap(),
+                most_slept_minute: guard.most_slept_minute(),
+                histogram: guard.histogram.clone(),
+            },
+            None => GuardStats {
+                guard_id,
+                shifts,
+                total_asleep: 0,
+                longest_nap: 0,
+                most_slept_minute: None,
+                histogram: vec![0; bucket.len()],
+            },
+        })
+        .collect()
+}
+
+pub fn sort_report(report: &mut [GuardStats], key: SortKey) {
+    match key {
+        SortKey::Id => report.sort_by_key(|g| g.guard_id),
+        SortKey::Shifts => report.sort_by_key(|g| Reverse(g.shifts)),
+        SortKey::TotalAsleep => report.sort_by_key(|g| Reverse(g.total_asleep)),
+        SortKey::MeanAsleep => report.sort_by(|a, b| {
+            b.mean_asleep()
+                .partial_cmp(&a.mean_asleep())
+                .expect("means are never NaN")
+        }),
+        SortKey::LongestNap => report.sort_by_key(|g| Reverse(g.longest_nap)),
+        SortKey::MinuteCount => {
+            report.sort_by_key(|g| Reverse(g.most_slept_minute.map_or(0, |(_, times)| times)))
+        }
+    }
+}
+
+fn most_slept_fields(stats: &GuardStats) -> (String, String) {
+    match stats.most_slept_minute {
+        Some((minute, times)) => (minute.to_string(), times.to_string()),
+        None => (String::new(), String::from("0")),
+    }
+}
+
+pub fn to_csv(report: &[GuardStats]) -> String {
+    let minutes = report.first().map_or(0, |g| g.histogram.len());
+
+    let mut csv = String::from(
+        "guard_id,shifts,total_asleep,mean_asleep,longest_nap,most_slept_minute,most_slept_count",
+    );
+    for minute in 0..minutes {
+        csv.push_str(&format!(",p{}", minute));
+    }
+    csv.push('\n');
+
+    for g in report {
+        let (minute, times) = most_slept_fields(g);
+        csv.push_str(&format!(
+            "{},{},{},{:.4},{},{},{}",
+            g.guard_id,
+            g.shifts,
+            g.total_asleep,
+            g.mean_asleep(),
+            g.longest_nap,
+            minute,
+            times
+        ));
+        for probability in g.probabilities() {
+            csv.push_str(&format!(",{:.4}", probability));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn to_json(report: &[GuardStats]) -> String {
+    let rows: Vec<String> = report
+        .iter()
+        .map(|g| {
+            let (minute, times) = most_slept_fields(g);
+            let probabilities: Vec<String> = g
+                .probabilities()
+                .iter()
+                .map(|p| format!("{:.4}", p))
+                .collect();
+            format!(
+                "  {{\"guard_id\": {}, \"shifts\": {}, \"total_asleep\": {}, \"mean_asleep\": {:.4}, \"longest_nap\": {}, \"most_slept_minute\": {}, \"most_slept_count\": {}, \"probabilities\": [{}]}}",
+                g.guard_id,
+                g.shifts,
+                g.total_asleep,
+                g.mean_asleep(),
+                g.longest_nap,
+                if minute.is_empty() { String::from("null") } else { minute },
+                times,
+                probabilities.join(", ")
+            )
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return String::from("[]\n");
+    }
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+// The table leaves out the per-minute probabilities and shows only the
+// chance of being asleep at the guard's most-slept minute.
+pub fn to_table(report: &[GuardStats]) -> String {
+    let mut table = format!(
+        "{:>6} {:>6} {:>6} {:>7} {:>7} {:>6} {:>6} {:>7}\n",
+        "guard", "shifts", "total", "mean", "longest", "minute", "count", "p"
+    );
+    for g in report {
+        let (minute, times) = most_slept_fields(g);
+        let probability = g
+            .most_slept_minute
+            .map_or(0.0, |(minute, _)| g.probability(minute));
+        table.push_str(&format!(
+            "{:>6} {:>6} {:>6} {:>7.2} {:>7} {:>6} {:>6} {:>7.4}\n",
+            g.guard_id,
+            g.shifts,
+            g.total_asleep,
+            g.mean_asleep(),
+            g.longest_nap,
+            minute,
+            times,
+            probability
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_records;
+    use crate::tests::EXAMPLE;
+
+    // The puzzle log plus a guard who works a shift without ever sleeping.
+    fn stats() -> Vec<GuardStats> {
+        let input = format!("{}\n[1518-11-06 00:00] Guard #7 begins shift", EXAMPLE);
+        guard_report(&get_records(&input), Bucket::MinuteOfHour)
+    }
+
+    #[test]
+    fn every_guard_on_shift_gets_a_row() {
+        let report = stats();
+        let rows: Vec<(GuardId, u32, u32, u32)> = report
+            .iter()
+            .map(|g| (g.guard_id, g.shifts, g.total_asleep, g.longest_nap))
+            .collect();
+
+        assert_eq!(rows, vec![(7, 1, 0, 0), (10, 2, 50, 25), (99, 3, 30, 10)]);
+        let minutes: Vec<Option<(Minute, u32)>> =
+            report.iter().map(|g| g.most_slept_minute).collect();
+        assert_eq!(minutes, vec![None, Some((24, 2)), Some((45, 3))]);
+        assert_eq!(report[1].mean_asleep(), 25.0);
+        assert_eq!(report[1].probability(24), 1.0);
+        assert_eq!(report[1].probability(5), 0.5);
+        assert_eq!(report[2].probabilities()[40], 2.0 / 3.0);
+    }
+
+    #[test]
+    fn sorts_sleepiest_first() {
+        let mut report = stats();
+
+        sort_report(&mut report, SortKey::Shifts);
+        let ids: Vec<GuardId> = report.iter().map(|g| g.guard_id).collect();
+        assert_eq!(ids, vec![99, 10, 7]);
+
+        sort_report(&mut report, SortKey::MeanAsleep);
+        let ids: Vec<GuardId> = report.iter().map(|g| g.guard_id).collect();
+        assert_eq!(ids, vec![10, 99, 7]);
+
+        sort_report(&mut report, SortKey::Id);
+        let ids: Vec<GuardId> = report.iter().map(|g| g.guard_id).collect();
+        assert_eq!(ids, vec![7, 10, 99]);
+    }
+
+    #[test]
+    fn exports_carry_per_minute_probabilities() {
+        let report = stats();
+
+        let csv = to_csv(&report[..1]);
+        let (header, row) = csv.split_once('\n').unwrap();
+        assert!(header.starts_with(
+            "guard_id,shifts,total_asleep,mean_asleep,longest_nap,most_slept_minute,most_slept_count,p0,p1,"
+        ));
+        assert!(header.ends_with(",p59"));
+        assert!(row.starts_with("7,1,0,0.0000,0,,0,0.0000,"));
+
+        let json = to_json(&report[1..2]);
+        assert!(json.starts_with(
+            "[\n  {\"guard_id\": 10, \"shifts\": 2, \"total_asleep\": 50, \"mean_asleep\": 25.0000, \"longest_nap\": 25, \"most_slept_minute\": 24, \"most_slept_count\": 2, \"probabilities\": [0.0000, "
+        ));
+        assert!(to_json(&report[..1]).contains("\"most_slept_minute\": null"));
+        assert_eq!(to_json(&[]), "[]\n");
+    }
+}
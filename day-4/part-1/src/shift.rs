@@ -0,0 +1,144 @@
+use crate::GuardEvent;
+use crate::GuardId;
+use crate::Record;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Timelike;
+
+#[derive(Debug, PartialEq)]
+pub struct Shift {
+    pub date: NaiveDate,
+    pub guard_id: GuardId,
+    pub asleep: u64,
+}
+
+impl Shift {
+    pub fn is_asleep(&self, minute: u32) -> bool {
+        minute < 60 && self.asleep & (1 << minute) != 0
+    }
+}
+
+// A shift is dated by the midnight hour it covers, so a guard who starts
+// just before midnight is listed under the following day. Only minutes in
+// that midnight hour are marked.
+pub fn get_shifts(records: &[Record]) -> Vec<Shift> {
+    let mut shifts: Vec<Shift> = Vec::new();
+    let mut time_fell_asleep = None;
+
+    for record in records {
+        match record.event {
+            GuardEvent::BeginShift(guard_id) => {
+                let start = record.timestamp;
+                let date = if start.hour() >= 12 {
+                    (start + Duration::days(1)).date_naive()
+                } else {
+                    start.date_naive()
+                };
+                shifts.push(Shift {
+                    date,
+                    guard_id,
+                    asleep: 0,
+                });
+                time_fell_asleep = None;
+            }
+            GuardEvent::FallAsleep => {
+                time_fell_asleep.get_or_insert(record.timestamp);
+            }
+            GuardEvent::WakeUp => {
+                let (shift, started_sleeping) = match (shifts.last_mut(), time_fell_asleep.take()) {
+                    (Some(shift), Some(started_sleeping)) => (shift, started_sleeping),
+                    _ => continue,
+                };
+
+                let mut time = started_sleeping;
+                while time < record.timestamp {
+                    if time.date_naive() == shift.date && time.hour() == 0 {
+                        shift.asleep |= 1 << time.minute();
+                    }
+                    time += Duration::minutes(1);
+                }
+            }
+        }
+    }
+
+    shifts
+}
+
+pub fn render_timeline(shifts: &[Shift]) -> String {
+    let id_width = shifts
+        .iter()
+        .map(|shift| format!("#{}", shift.guard_id).len())
+        .max()
+        .unwrap_or(2)
+        .max(2);
+    let indent = " ".repeat(5 + 2 + id_width + 2);
+
+    let tens: String = (0..60).map(|m| (b'0' + m / 10) as char).collect();
+    let ones: String = (0..60).map(|m| (b'0' + m % 10) as char).collect();
+
+    let mut table = format!(
+        "Date   {:<width$}Minute\n{}{}\n{}{}\n",
+        "ID",
+        indent,
+        tens,
+        indent,
+        ones,
+        width = id_width + 2
+    );
+
+    for shift in shifts {
+        let marks: String = (0..60)
+            .map(|m| if shift.is_asleep(m) { '#' } else { '.' })
+            .collect();
+        table.push_str(&format!(
+            "{:02}-{:02}  {:<width$}  {}\n",
+            shift.date.month(),
+            shift.date.day(),
+            format!("#{}", shift.guard_id),
+            marks,
+            width = id_width
+        ));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_records;
+    use crate::tests::EXAMPLE;
+
+    #[test]
+    fn shifts() {
+        let shifts = get_shifts(&get_records(EXAMPLE));
+        assert_eq!(shifts.len(), 5);
+        assert_eq!(
+            shifts[1].date,
+            NaiveDate::from_ymd_opt(1518, 11, 2).unwrap()
+        );
+        assert_eq!(shifts[1].guard_id, 99);
+        assert_eq!(shifts[0].asleep.count_ones(), 45);
+        assert!(shifts[0].is_asleep(5));
+        assert!(!shifts[0].is_asleep(25));
+    }
+
+    #[test]
+    fn timeline() {
+        let expected = "\
+Date   ID   Minute
+            000000000011111111112222222222333333333344444444445555555555
+            012345678901234567890123456789012345678901234567890123456789
+11-01  #10  .....####################.....#########################.....
+11-02  #99  ........................................##########..........
+11-03  #10  ........................#####...............................
+11-04  #99  ....................................##########..............
+11-05  #99  .............................................##########.....
+";
+        assert_eq!(
+            render_timeline(&get_shifts(&get_records(EXAMPLE))),
+            expected
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::Bucket;
+use crate::GuardId;
+use crate::Minute;
+use crate::Nap;
+use std::collections::BTreeMap;
+use std::ops::Sub;
+
+#[derive(Debug)]
+pub struct GuardSleep {
+    pub naps: Vec<(Minute, u32)>,
+    pub histogram: Vec<u32>,
+}
+
+impl GuardSleep {
+    pub fn total_asleep(&self) -> u32 {
+        self.naps.iter().map(|&(_start, length)| length).sum()
+    }
+
+    pub fn longest_nap(&self) -> u32 {
+        self.naps
+            .iter()
+            .map(|&(_start, length)| length)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Ties go to the earliest minute.
+    pub fn most_slept_minute(&self) -> Option<(Minute, u32)> {
+        self.histogram
+            .iter()
+            .enumerate()
+            .filter(|&(_minute, &times)| times > 0)
+            .fold(
+                None,
+                |best: Option<(Minute, u32)>, (minute, &times)| match best {
+                    Some((_, best_times)) if best_times >= times => best,
+                    _ => Some((minute as Minute, times)),
+                },
+            )
+    }
+}
+
+// Naps are kept as (first minute, length) intervals per guard. The minute by
+// minute histogram is built from a difference array over the bucket's
+// minutes, so each nap costs two updates however long it lasts; naps running
+// past the end of the hour (or day) wrap around to its start.
+#[derive(Debug)]
+pub struct SleepModel {
+    pub bucket: Bucket,
+    pub guards: BTreeMap<GuardId, GuardSleep>,
+}
+
+impl SleepModel {
+    pub fn new(naps: &[Nap], bucket: Bucket) -> Self {
+        let len = bucket.len();
+        let mut intervals: BTreeMap<GuardId, Vec<(Minute, u32)>> = BTreeMap::new();
+
+        for nap in naps {
+            let length = nap.end.sub(nap.start).num_minutes();
+            if length <= 0 {
+                continue;
+            }
+            intervals
+                .entry(nap.guard_id)
+                .or_default()
+                .push((bucket.minute(nap.start), length as u32));
+        }
+
+        let guards = intervals
+            .into_iter()
+            .map(|(guard_id, naps)| {
+                let mut difference = vec![0i64; len + 1];
+                for &(start, length) in naps.iter() {
+                    let (start, length) = (start as usize, length as usize);
+                    let (cycles, rest) = (length / len, length % len);
+
+                    difference[0] += cycles as i64;
+                    difference[len] -= cycles as i64;
+
+                    let end = start + rest;
+                    difference[start] += 1;
+                    if end <= len {
+                        difference[end] -= 1;
+                    } else {
+                        difference[len] -= 1;
+                        difference[0] += 1;
+                        difference[end - len] -= 1;
+                    }
+                }
+
+                let mut running = 0;
+                let histogram = difference[..len]
+                    .iter()
+                    .map(|delta| {
+                        running += delta;
+                        running as u32
+                    })
+                    .collect();
+
+                (guard_id, GuardSleep { naps, histogram })
+            })
+            .collect();
+
+        SleepModel { bucket, guards }
+    }
+
+    // How many naps, summed over every guard and every date, covered each
+    // minute. Naps on different nights all count, so this is not how many
+    // guards were asleep at the same moment.
+    pub fn naps_per_minute(&self) -> Vec<u32> {
+        let mut naps = vec![0; self.bucket.len()];
+        for guard in self.guards.values() {
+            for (total, times) in naps.iter_mut().zip(guard.histogram.iter()) {
+                *total += times;
+            }
+        }
+        naps
+    }
+
+    // How many different guards slept through each minute on at least one
+    // date.
+    pub fn sleepers_per_minute(&self) -> Vec<u32> {
+        (0..self.bucket.len())
+            .map(|minute| {
+                self.guards
+                    .values()
+                    .filter(|guard| guard.histogram[minute] > 0)
+                    .count() as u32
+            })
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (guard_id, guard) in self.guards.iter() {
+            out.push_str(&format!(
+                "#{} asleep {} minutes, longest nap {}\n",
+                guard_id,
+                guard.total_asleep(),
+                guard.longest_nap()
+            ));
+        }
+        let sleepers = self.sleepers_per_minute();
+        for (minute, naps) in self.naps_per_minute().iter().enumerate() {
+            out.push_str(&format!(
+                "{:04} {} naps, {} guards\n",
+                minute, naps, sleepers[minute]
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_naps;
+    use crate::get_records;
+    use crate::tests::ACROSS_MIDNIGHT;
+    use crate::tests::EXAMPLE;
+
+    fn model(input: &str, bucket: Bucket) -> SleepModel {
+        SleepModel::new(&get_naps(&get_records(input)), bucket)
+    }
+
+    #[test]
+    fn guard_queries() {
+        let model = model(EXAMPLE, Bucket::MinuteOfHour);
+
+        let ten = &model.guards[&10];
+        assert_eq!(ten.total_asleep(), 50);
+        assert_eq!(ten.longest_nap(), 25);
+        assert_eq!(ten.most_slept_minute(), Some((24, 2)));
+
+        let ninety_nine = &model.guards[&99];
+        assert_eq!(ninety_nine.total_asleep(), 30);
+        assert_eq!(ninety_nine.most_slept_minute(), Some((45, 3)));
+        assert!(!model.guards.contains_key(&1));
+    }
+
+    #[test]
+    fn naps_per_minute() {
+        let naps = model(EXAMPLE, Bucket::MinuteOfHour).naps_per_minute();
+        assert_eq!(naps.len(), 60);
+        assert_eq!(naps[4], 0);
+        assert_eq!(naps[24], 2);
+        assert_eq!(naps[45], 4);
+    }
+
+    #[test]
+    fn sleepers_per_minute() {
+        let sleepers = model(EXAMPLE, Bucket::MinuteOfHour).sleepers_per_minute();
+        assert_eq!(sleepers.len(), 60);
+        assert_eq!(sleepers[4], 0);
+        assert_eq!(sleepers[24], 1);
+        assert_eq!(sleepers[45], 2);
+    }
+
+    #[test]
+    fn naps_wrap_around_the_bucket() {
+        let by_hour = model(ACROSS_MIDNIGHT, Bucket::MinuteOfHour);
+        let asleep: Vec<usize> = (0..60)
+            .filter(|&m| by_hour.guards[&10].histogram[m] > 0)
+            .collect();
+        assert_eq!(asleep, vec![0, 1, 2, 58, 59]);
+
+        let by_day = model(ACROSS_MIDNIGHT, Bucket::MinuteOfDay);
+        let asleep: Vec<usize> = (0..1440)
+            .filter(|&m| by_day.guards[&10].histogram[m] > 0)
+            .collect();
+        assert_eq!(asleep, vec![0, 1, 2, 1438, 1439]);
+    }
+
+    #[test]
+    fn naps_longer_than_the_bucket() {
+        let input = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:10] falls asleep
+[1518-11-01 01:15] wakes up";
+
+        let model = model(input, Bucket::MinuteOfHour);
+        let histogram = &model.guards[&10].histogram;
+        assert_eq!(histogram[9], 1);
+        assert_eq!(histogram[10], 2);
+        assert_eq!(histogram[14], 2);
+        assert_eq!(histogram[15], 1);
+        assert_eq!(histogram.iter().sum::<u32>(), 65);
+    }
+}
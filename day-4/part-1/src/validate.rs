@@ -0,0 +1,206 @@
+use crate::GuardEvent;
+use crate::GuardId;
+use crate::Record;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Warn,
+    Strict,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Anomaly {
+    EventBeforeShift {
+        line: usize,
+    },
+    WakeWithoutSleep {
+        line: usize,
+        guard_id: GuardId,
+    },
+    DoubleSleep {
+        line: usize,
+        first_line: usize,
+        guard_id: GuardId,
+    },
+    UnclosedNap {
+        line: usize,
+        guard_id: GuardId,
+    },
+    DuplicateTimestamp {
+        line: usize,
+        other_line: usize,
+    },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Anomaly::EventBeforeShift { line } => {
+                write!(f, "line {}: event before any guard began a shift", line)
+            }
+            Anomaly::WakeWithoutSleep { line, guard_id } => {
+                write!(
+                    f,
+                    "line {}: guard #{} wakes up without falling asleep",
+                    line, guard_id
+                )
+            }
+            Anomaly::DoubleSleep {
+                line,
+                first_line,
+                guard_id,
+            } => write!(
+                f,
+                "line {}: guard #{} falls asleep again (already asleep since line {})",
+                line, guard_id, first_line
+            ),
+            Anomaly::UnclosedNap { line, guard_id } => write!(
+                f,
+                "line {}: guard #{} falls asleep and never wakes up before the shift ends",
+                line, guard_id
+            ),
+            Anomaly::DuplicateTimestamp { line, other_line } => {
+                write!(f, "line {}: same timestamp as line {}", line, other_line)
+            }
+        }
+    }
+}
+
+// Walks records in timestamp order and reports everything that doesn't fit
+// the begin shift / fall asleep / wake up cycle. Lines refer to the input.
+pub fn validate(records: &[Record]) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+
+    let mut current_guard = None;
+    let mut asleep_since: Option<&Record> = None;
+    let mut previous: Option<&Record> = None;
+
+    for record in records {
+        if let Some(previous) = previous {
+            if previous.timestamp == record.timestamp {
+                anomalies.push(Anomaly::DuplicateTimestamp {
+                    line: record.line,
+                    other_line: previous.line,
+                });
+            }
+        }
+        previous = Some(record);
+
+        let guard_id = match (&record.event, current_guard) {
+            (GuardEvent::BeginShift(guard_id), _) => {
+                if let (Some(sleep), Some(previous_guard)) = (asleep_since.take(), current_guard) {
+                    anomalies.push(Anomaly::UnclosedNap {
+                        line: sleep.line,
+                        guard_id: previous_guard,
+                    });
+                }
+                current_guard = Some(*guard_id);
+                continue;
+            }
+            (_, None) => {
+                anomalies.push(Anomaly::EventBeforeShift { line: record.line });
+                continue;
+            }
+            (_, Some(guard_id)) => guard_id,
+        };
+
+        match record.event {
+            GuardEvent::FallAsleep => match asleep_since {
+                Some(first) => anomalies.push(Anomaly::DoubleSleep {
+                    line: record.line,
+                    first_line: first.line,
+                    guard_id,
+                }),
+                None => asleep_since = Some(record),
+            },
+            GuardEvent::WakeUp => {
+                if asleep_since.take().is_none() {
+                    anomalies.push(Anomaly::WakeWithoutSleep {
+                        line: record.line,
+                        guard_id,
+                    });
+                }
+            }
+            GuardEvent::BeginShift(_) => {}
+        }
+    }
+
+    if let (Some(sleep), Some(guard_id)) = (asleep_since, current_guard) {
+        anomalies.push(Anomaly::UnclosedNap {
+            line: sleep.line,
+            guard_id,
+        });
+    }
+
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_records;
+
+    #[test]
+    fn clean_log() {
+        let records = get_records(
+            "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep\n[1518-11-01 00:25] wakes up",
+        );
+        assert!(validate(&records).is_empty());
+    }
+
+    #[test]
+    fn every_anomaly() {
+        let records = get_records(
+            "\
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:10] Guard #10 begins shift
+[1518-11-01 00:15] wakes up
+[1518-11-01 00:20] falls asleep
+[1518-11-01 00:25] falls asleep
+[1518-11-01 00:30] wakes up
+[1518-11-01 00:40] falls asleep
+[1518-11-02 00:00] Guard #99 begins shift
+[1518-11-02 00:00] falls asleep",
+        );
+
+        assert_eq!(
+            validate(&records),
+            vec![
+                Anomaly::EventBeforeShift { line: 1 },
+                Anomaly::WakeWithoutSleep {
+                    line: 3,
+                    guard_id: 10
+                },
+                Anomaly::DoubleSleep {
+                    line: 5,
+                    first_line: 4,
+                    guard_id: 10
+                },
+                Anomaly::UnclosedNap {
+                    line: 7,
+                    guard_id: 10
+                },
+                Anomaly::DuplicateTimestamp {
+                    line: 9,
+                    other_line: 8
+                },
+                Anomaly::UnclosedNap {
+                    line: 9,
+                    guard_id: 99
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn line_numbers_follow_input_not_sorted_order() {
+        let records = get_records(
+            "[1518-11-01 00:25] wakes up\n[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:30] wakes up\n[1518-11-01 00:05] falls asleep",
+        );
+        assert_eq!(
+            validate(&records)[0].to_string(),
+            "line 3: guard #10 wakes up without falling asleep"
+        );
+    }
+}
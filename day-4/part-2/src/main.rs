@@ -1,9 +1,25 @@
-use chrono::offset::TimeZone;
+mod grammar;
+mod report;
+mod sleep;
+mod strategy;
+mod validate;
+
 use chrono::DateTime;
+use chrono::Duration;
 use chrono::Timelike;
 use chrono::Utc;
-use std::collections::HashMap;
-use std::ops::Sub;
+use grammar::Grammar;
+use grammar::GrammarError;
+use report::SortKey;
+use sleep::SleepModel;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::process;
+use strategy::Choice;
+use strategy::SleepLog;
+use strategy::Strategy;
+use validate::Mode;
 
 type Minute = u32;
 type GuardId = u32;
@@ -15,74 +31,202 @@ enum GuardEvent {
     WakeUp,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bucket {
+    MinuteOfHour,
+    MinuteOfDay,
+}
+
+impl Bucket {
+    fn len(self) -> usize {
+        match self {
+            Bucket::MinuteOfHour => 60,
+            Bucket::MinuteOfDay => 24 * 60,
+        }
+    }
+
+    fn minute(self, time: DateTime<Utc>) -> Minute {
+        match self {
+            Bucket::MinuteOfHour => time.minute(),
+            Bucket::MinuteOfDay => time.hour() * 60 + time.minute(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Nap {
+    guard_id: GuardId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq)]
+enum NapWarning {
+    OutsideMidnightHour(Nap),
+    NotPositive(Nap),
+}
+
+impl fmt::Display for NapWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (nap, problem) = match self {
+            NapWarning::OutsideMidnightHour(nap) => (nap, "falls outside the midnight hour"),
+            NapWarning::NotPositive(nap) => (nap, "doesn't last any time"),
+        };
+        write!(
+            f,
+            "guard #{} nap from {} to {} {}",
+            nap.guard_id,
+            nap.start.format("%Y-%m-%d %H:%M"),
+            nap.end.format("%Y-%m-%d %H:%M"),
+            problem
+        )
+    }
+}
+
 #[derive(Debug)]
 struct Record {
+    line: usize,
     timestamp: DateTime<Utc>,
     event: GuardEvent,
 }
 
-impl From<&str> for Record {
-    fn from(string: &str) -> Self {
-        let (time_str, event_str) = string.split_at(18);
-
-        let timestamp = Utc
-            .datetime_from_str(time_str, "[%Y-%m-%d %H:%M]")
-            .expect("couldn't parse date");
-
-        let event = match event_str.trim() {
-            "wakes up" => GuardEvent::WakeUp,
-            "falls asleep" => GuardEvent::FallAsleep,
-            s => {
-                let guard_num = s
-                    .trim_matches(|c: char| !c.is_numeric())
-                    .parse()
-                    .expect("couldn't parse guard number");
-                GuardEvent::BeginShift(guard_num)
-            }
-        };
+fn main() {
+    let mut bucket = Bucket::MinuteOfHour;
+    let mut mode = Mode::Warn;
+    let mut strategy: Box<dyn Strategy> = Box::new(strategy::MostFrequentMinute);
+    let mut explain = false;
+    let mut naps = false;
+    let mut log_path = None;
+    let mut grammar_path = None;
+    let mut report_format = None;
+    let mut sort = SortKey::Id;
 
-        Record { timestamp, event }
+    let mut args = env::args().skip(1).peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "naps" => naps = true,
+            "--log" => log_path = args.next(),
+            "--grammar" => grammar_path = args.next(),
+            "report" => {
+                let format = args.next_if(|f| ["csv", "json", "table"].contains(&f.as_str()));
+                report_format = Some(format.unwrap_or_else(|| String::from("table")));
+            }
+            "--sort" => sort = SortKey::from(args.next().unwrap_or_default().as_str()),
+            "--minute-of-day" => bucket = Bucket::MinuteOfDay,
+            "--strict" => mode = Mode::Strict,
+            "--explain" => explain = true,
+            "--strategy" => {
+                let name = args.next().unwrap_or_default();
+                strategy = match strategy::named(&name) {
+                    Some(strategy) => strategy,
+                    None => {
+                        let names: Vec<&str> = strategy::all().iter().map(|s| s.name()).collect();
+                        eprintln!(
+                            "unknown strategy `{}`, expected one of: {}",
+                            name,
+                            names.join(", ")
+                        );
+                        process::exit(1);
+                    }
+                };
+            }
+            other => {
+                eprintln!("unknown argument `{}`", other);
+                process::exit(1);
+            }
+        }
     }
-}
 
-fn main() {
-    let input = include_str!("./input.txt");
-    let s = get_solution(input);
-    println!("{:?}", s);
-}
+    let input = match log_path {
+        Some(path) => fs::read_to_string(path).expect("couldn't read log"),
+        None => String::from(include_str!("./input.txt")),
+    };
+    let grammar = match grammar_path {
+        Some(path) => {
+            let text = fs::read_to_string(&path).expect("couldn't read grammar");
+            Grammar::read(&text).unwrap_or_else(|e| {
+                eprintln!("error: {}: {}", path, e);
+                process::exit(1);
+            })
+        }
+        None => Grammar::default(),
+    };
+    let records = get_records_with(&input, &grammar).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    });
 
-fn get_solution(input: &str) -> u32 {
-    let records = get_records(input);
+    if naps {
+        print!("{}", SleepModel::new(&get_naps(&records), bucket).render());
+        return;
+    }
+    if let Some(format) = report_format {
+        let mut stats = report::guard_report(&records, bucket);
+        report::sort_report(&mut stats, sort);
+        match format.as_str() {
+            "csv" => print!("{}", report::to_csv(&stats)),
+            "json" => print!("{}", report::to_json(&stats)),
+            _ => print!("{}", report::to_table(&stats)),
+        }
+        return;
+    }
 
-    let minutes_asleep = get_each_minute_spent_asleep_per_guard(&records);
+    let anomalies = validate::validate(&records);
+    if mode == Mode::Strict && !anomalies.is_empty() {
+        for anomaly in anomalies {
+            eprintln!("error: {}", anomaly);
+        }
+        process::exit(1);
+    }
+    for anomaly in anomalies {
+        eprintln!("warning: {}", anomaly);
+    }
 
-    let (frequent_guard, frequent_minute) = minutes_asleep
-        .iter()
-        .map(|(&guard_id, minutes)| {
-            let (most_slept_minute, times_slept) = minutes
-                .iter()
-                .max_by_key(|(_minute, &times_slept)| times_slept)
-                .expect("somehow this guard hasn't slept");
+    for warning in check_naps(&get_naps(&records)) {
+        eprintln!("warning: {}", warning);
+    }
 
-            (guard_id, minutes, most_slept_minute, times_slept)
-        })
-        .max_by_key(|(_guard_id, _minutes, _most_slept_minute, &times_slept)| times_slept)
-        .map(|(guard_id, _minutes, most_slept_minute, _times_slept)| (guard_id, most_slept_minute))
-        .expect("somehow no guards have slept.");
+    let choice =
+        get_solution(&records, bucket, strategy.as_ref()).expect("somehow no guards have slept.");
+    if explain {
+        println!("{}: {}", strategy.name(), choice.explanation);
+    }
+    println!("{:?}", choice.answer());
+}
 
-    frequent_guard * frequent_minute
+fn get_solution(records: &[Record], bucket: Bucket, strategy: &dyn Strategy) -> Option<Choice> {
+    strategy.choose(&SleepLog::new(records, bucket))
 }
 
+#[cfg(test)]
 fn get_records(input: &str) -> Vec<Record> {
-    let mut records: Vec<Record> = input.lines().map(Record::from).collect();
+    get_records_with(input, &Grammar::default()).expect("couldn't parse log")
+}
+
+fn get_records_with(input: &str, grammar: &Grammar) -> Result<Vec<Record>, GrammarError> {
+    let mut records = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = grammar.parse(line).map_err(|message| GrammarError {
+            line: i + 1,
+            message,
+        })?;
+        records.push(Record {
+            line: i + 1,
+            ..record
+        });
+    }
     records.sort_by_key(|r| r.timestamp);
-    records
+    Ok(records)
 }
 
-fn get_each_minute_spent_asleep_per_guard(
-    records: &[Record],
-) -> HashMap<GuardId, HashMap<Minute, u32>> {
-    let mut minutes_spent_asleep = HashMap::new();
+// Records that don't form a complete nap within one guard's shift are
+// skipped here, and a second sleep before waking is ignored so the nap runs
+// from the first; `validate::validate` is what reports them.
+fn get_naps(records: &[Record]) -> Vec<Nap> {
+    let mut naps = Vec::new();
 
     let mut current_guard = None;
     let mut time_fell_asleep = None;
@@ -91,28 +235,143 @@ fn get_each_minute_spent_asleep_per_guard(
         match record.event {
             GuardEvent::BeginShift(guard_id) => {
                 current_guard = Some(guard_id);
+                time_fell_asleep = None;
             }
             GuardEvent::FallAsleep => {
-                time_fell_asleep = Some(record.timestamp);
+                time_fell_asleep.get_or_insert(record.timestamp);
             }
             GuardEvent::WakeUp => {
-                let guard_id = current_guard.expect("a guard hasn't started their shift yet");
-                let started_sleeping = time_fell_asleep.expect("this guard isn't asleep");
-
-                let minute_started = started_sleeping.time().minute();
-                let num_minutes_slept = record.timestamp.sub(started_sleeping).num_minutes();
-
-                let times_minute_slept: &mut HashMap<Minute, u32> = minutes_spent_asleep
-                    .entry(guard_id)
-                    .or_insert_with(HashMap::new);
-
-                for i in 0..num_minutes_slept {
-                    let curr_minute = minute_started + (i as u32);
-                    *times_minute_slept.entry(curr_minute).or_insert(0) += 1;
+                if let (Some(guard_id), Some(started_sleeping)) =
+                    (current_guard, time_fell_asleep.take())
+                {
+                    naps.push(Nap {
+                        guard_id,
+                        start: started_sleeping,
+                        end: record.timestamp,
+                    });
                 }
             }
         }
     }
 
-    minutes_spent_asleep
+    naps
+}
+
+// Guards are only expected to sleep during the midnight hour, so naps that
+// start or end outside of it (or that don't last at all) are reported back
+// rather than silently counted or dropped.
+fn check_naps(naps: &[Nap]) -> Vec<NapWarning> {
+    naps.iter()
+        .filter_map(|nap| {
+            let last_minute = nap.end - Duration::minutes(1);
+            if nap.end <= nap.start {
+                Some(NapWarning::NotPositive(nap.clone()))
+            } else if nap.start.hour() != 0
+                || last_minute.hour() != 0
+                || last_minute.date_naive() != nap.start.date_naive()
+            {
+                Some(NapWarning::OutsideMidnightHour(nap.clone()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub const EXAMPLE: &str = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up";
+
+    pub const ACROSS_MIDNIGHT: &str = "\
+[1518-11-01 23:50] Guard #10 begins shift
+[1518-11-01 23:58] falls asleep
+[1518-11-02 00:03] wakes up";
+
+    #[test]
+    fn example() {
+        assert_eq!(
+            get_solution(
+                &get_records(EXAMPLE),
+                Bucket::MinuteOfHour,
+                &strategy::MostFrequentMinute
+            )
+            .map(|choice| choice.answer()),
+            Some(4455)
+        );
+        assert!(check_naps(&get_naps(&get_records(EXAMPLE))).is_empty());
+    }
+
+    #[test]
+    fn naps_outside_midnight_hour_are_reported() {
+        let naps = get_naps(&get_records(ACROSS_MIDNIGHT));
+        assert_eq!(
+            check_naps(&naps),
+            vec![NapWarning::OutsideMidnightHour(naps[0].clone())]
+        );
+        assert_eq!(
+            check_naps(&naps)[0].to_string(),
+            "guard #10 nap from 1518-11-01 23:58 to 1518-11-02 00:03 falls outside the midnight hour"
+        );
+
+        let late = get_naps(&get_records(
+            "[1518-11-01 00:00] Guard #1 begins shift\n[1518-11-01 00:50] falls asleep\n[1518-11-01 01:10] wakes up",
+        ));
+        assert_eq!(check_naps(&late).len(), 1);
+
+        let until_one = get_naps(&get_records(
+            "[1518-11-01 00:00] Guard #1 begins shift\n[1518-11-01 00:50] falls asleep\n[1518-11-01 01:00] wakes up",
+        ));
+        assert!(check_naps(&until_one).is_empty());
+    }
+
+    #[test]
+    fn empty_naps_are_reported() {
+        let naps = vec![Nap {
+            guard_id: 1,
+            start: get_records("[1518-11-01 00:10] falls asleep")[0].timestamp,
+            end: get_records("[1518-11-01 00:10] wakes up")[0].timestamp,
+        }];
+        assert_eq!(
+            check_naps(&naps),
+            vec![NapWarning::NotPositive(naps[0].clone())]
+        );
+    }
+
+    #[test]
+    fn double_sleep_keeps_the_first() {
+        let records = get_records(
+            "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:20] falls asleep
+[1518-11-01 00:25] wakes up",
+        );
+        let naps = get_naps(&records);
+
+        assert_eq!(naps.len(), 1);
+        assert_eq!(naps[0].start, records[1].timestamp);
+        assert_eq!(
+            validate::validate(&records)[0].to_string(),
+            "line 3: guard #10 falls asleep again (already asleep since line 2)"
+        );
+    }
 }
@@ -0,0 +1,246 @@
+use crate::get_naps;
+use crate::sleep::SleepModel;
+use crate::Bucket;
+use crate::GuardEvent;
+use crate::GuardId;
+use crate::Minute;
+use crate::Record;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct SleepLog {
+    pub sleep: SleepModel,
+    pub shifts: HashMap<GuardId, u32>,
+}
+
+impl SleepLog {
+    pub fn new(records: &[Record], bucket: Bucket) -> Self {
+        let sleep = SleepModel::new(&get_naps(records), bucket);
+
+        let mut shifts = HashMap::new();
+        for record in records {
+            if let GuardEvent::BeginShift(guard_id) = record.event {
+                *shifts.entry(guard_id).or_insert(0) += 1;
+            }
+        }
+
+        SleepLog { sleep, shifts }
+    }
+
+    fn most_slept_minute(&self, guard_id: GuardId) -> Option<(Minute, u32)> {
+        self.sleep.guards.get(&guard_id)?.most_slept_minute()
+    }
+
+    // Guards are visited in id order so ties always go to the lowest id.
+    fn guards(&self) -> Vec<GuardId> {
+        self.sleep.guards.keys().copied().collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Choice {
+    pub guard_id: GuardId,
+    pub minute: Minute,
+    pub explanation: String,
+}
+
+impl Choice {
+    pub fn answer(&self) -> u32 {
+        self.guard_id * self.minute
+    }
+}
+
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+    fn choose(&self, log: &SleepLog) -> Option<Choice>;
+}
+
+pub struct MostMinutesAsleep;
+pub struct MostFrequentMinute;
+pub struct LongestNap;
+pub struct HighestProbability;
+pub struct MostAsleepPerShift;
+
+pub fn all() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(MostMinutesAsleep),
+        Box::new(MostFrequentMinute),
+        Box::new(LongestNap),
+        Box::new(HighestProbability),
+        Box::new(MostAsleepPerShift),
+    ]
+}
+
+pub fn named(name: &str) -> Option<Box<dyn Strategy>> {
+    all().into_iter().find(|strategy| strategy.name() == name)
+}
+
+// Shared by the strategies that first pick a guard by some score and then
+// take the minute that guard slept through most often.
+fn pick_guard_then_minute<F>(log: &SleepLog, score: F, reason: &str) -> Option<Choice>
+where
+    F: Fn(GuardId) -> f64,
+{
+    let guard_id = log.guards().into_iter().rev().max_by(|&a, &b| {
+        score(a)
+            .partial_cmp(&score(b))
+            .expect("scores are never NaN")
+    })?;
+    let (minute, times) = log.most_slept_minute(guard_id)?;
+
+    Some(Choice {
+        guard_id,
+        minute,
+        explanation: format!(
+            "guard #{} {} ({}); they were asleep at minute {} on {} occasions, more than any other minute",
+            guard_id,
+            reason,
+            (score(guard_id) * 100.0).round() / 100.0,
+            minute,
+            times
+        ),
+    })
+}
+
+impl Strategy for MostMinutesAsleep {
+    fn name(&self) -> &'static str {
+        "most-minutes"
+    }
+
+    fn choose(&self, log: &SleepLog) -> Option<Choice> {
+        pick_guard_then_minute(
+            log,
+            |guard_id| log.sleep.guards[&guard_id].total_asleep() as f64,
+            "slept the most minutes in total",
+        )
+    }
+}
+
+impl Strategy for MostFrequentMinute {
+    fn name(&self) -> &'static str {
+        "most-frequent-minute"
+    }
+
+    fn choose(&self, log: &SleepLog) -> Option<Choice> {
+        let (guard_id, (minute, times)) = log
+            .guards()
+            .into_iter()
+            .filter_map(|guard_id| Some((guard_id, log.most_slept_minute(guard_id)?)))
+            .max_by_key(|&(guard_id, (_minute, times))| (times, Reverse(guard_id)))?;
+
+        Some(Choice {
+            guard_id,
+            minute,
+            explanation: format!(
+                "guard #{} was asleep at minute {} on {} occasions, more often than any guard at any other minute",
+                guard_id, minute, times
+            ),
+        })
+    }
+}
+
+impl Strategy for LongestNap {
+    fn name(&self) -> &'static str {
+        "longest-nap"
+    }
+
+    fn choose(&self, log: &SleepLog) -> Option<Choice> {
+        let longest = |guard_id: GuardId| log.sleep.guards[&guard_id].longest_nap() as f64;
+        pick_guard_then_minute(log, longest, "took the longest single nap in minutes")
+    }
+}
+
+impl Strategy for HighestProbability {
+    fn name(&self) -> &'static str {
+        "highest-probability"
+    }
+
+    fn choose(&self, log: &SleepLog) -> Option<Choice> {
+        let (guard_id, minute, times, probability) = log
+            .guards()
+            .into_iter()
+            .filter_map(|guard_id| {
+                let (minute, times) = log.most_slept_minute(guard_id)?;
+                let shifts = log.shifts.get(&guard_id).copied().unwrap_or(0).max(1);
+                Some((guard_id, minute, times, times as f64 / shifts as f64))
+            })
+            .rev()
+            .max_by(|a, b| a.3.partial_cmp(&b.3).expect("probabilities are never NaN"))?;
+
+        Some(Choice {
+            guard_id,
+            minute,
+            explanation: format!(
+                "guard #{} was asleep at minute {} on {} of their {} shifts (p = {:.3}), the highest chance of any guard being asleep at any minute",
+                guard_id,
+                minute,
+                times,
+                log.shifts.get(&guard_id).copied().unwrap_or(0),
+                probability
+            ),
+        })
+    }
+}
+
+impl Strategy for MostAsleepPerShift {
+    fn name(&self) -> &'static str {
+        "most-per-shift"
+    }
+
+    fn choose(&self, log: &SleepLog) -> Option<Choice> {
+        let per_shift = |guard_id: GuardId| {
+            let total = log.sleep.guards[&guard_id].total_asleep();
+            let shifts = log.shifts.get(&guard_id).copied().unwrap_or(0).max(1);
+            total as f64 / shifts as f64
+        };
+        pick_guard_then_minute(log, per_shift, "slept the most minutes per shift worked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_records;
+    use crate::tests::EXAMPLE;
+
+    fn choose(name: &str) -> Choice {
+        let log = SleepLog::new(&get_records(EXAMPLE), Bucket::MinuteOfHour);
+        named(name)
+            .expect("unknown strategy")
+            .choose(&log)
+            .expect("no choice made")
+    }
+
+    #[test]
+    fn puzzle_strategies() {
+        assert_eq!(choose("most-minutes").answer(), 240);
+        assert_eq!(choose("most-frequent-minute").answer(), 4455);
+        assert!(choose("most-minutes")
+            .explanation
+            .starts_with("guard #10 slept the most minutes in total (50)"));
+    }
+
+    #[test]
+    fn additional_strategies() {
+        let longest = choose("longest-nap");
+        assert_eq!((longest.guard_id, longest.minute), (10, 24));
+
+        let probability = choose("highest-probability");
+        assert_eq!((probability.guard_id, probability.minute), (10, 24));
+        assert!(probability.explanation.contains("on 2 of their 2 shifts"));
+
+        let per_shift = choose("most-per-shift");
+        assert_eq!((per_shift.guard_id, per_shift.minute), (10, 24));
+    }
+
+    #[test]
+    fn no_sleep_no_choice() {
+        let log = SleepLog::new(
+            &get_records("[1518-11-01 00:00] Guard #10 begins shift"),
+            Bucket::MinuteOfHour,
+        );
+        assert!(all().iter().all(|strategy| strategy.choose(&log).is_none()));
+        assert!(named("nonsense").is_none());
+    }
+}